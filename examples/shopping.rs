@@ -0,0 +1,64 @@
+//! A shopping trip with `Ford`, set up programmatically instead of through the REPL.
+//!
+//! Run with `cargo run --example shopping`.
+use std::error::Error as StdError;
+
+use rust_decimal_macros::dec;
+
+use intra::{Ford, Quantity};
+
+fn main() -> Result<(), Box<dyn StdError>> {
+    let mut ford = Ford::new();
+
+    // Setup your dialect of intergalactic numerals.
+    ford.define_word("glob", 'I')?;
+    ford.define_word("prok", 'V')?;
+    ford.define_word("pish", 'X')?;
+    ford.define_word("tegj", 'L')?;
+
+    // Setup the prices of items you're interested in.
+    ford.define_item("glob glob", "Silver", dec!(34))?;
+    ford.define_item("glob prok", "Gold", dec!(57800))?;
+    ford.define_item("pish pish", "Iron", dec!(3910))?;
+
+    // Translate a numeral, and keep its value as a typed quantity.
+    let roman = ford.language().translate("pish tegj glob glob")?;
+    let quantity = Quantity::from(&roman);
+    println!("{:<40} => {roman} = {quantity}", "pish tegj glob glob");
+
+    // Price a few shopping lists, one `<number> <Item>` per line.
+    let lists = [
+        "glob prok Silver",
+        "glob prok Gold",
+        "glob prok Iron\nglob glob Silver",
+        "glob prok Copper",
+    ];
+    for list in lists {
+        let list_name = list.replace('\n', ", ");
+        match ford.total_cost(list.lines()) {
+            Ok(credits) => println!("{list_name:<40} => {credits}"),
+            Err(err) => println!("{list_name:<40} => error: {err}"),
+        }
+    }
+
+    // Prices of a single unit, sorted by item.
+    let mut items = ford.items().collect::<Vec<_>>();
+    items.sort();
+    for (item, price) in items {
+        println!("{item:<40} => {price} each");
+    }
+    if let Some(average) = ford.average_price() {
+        println!("{:<40} => {average}", "average price");
+    }
+
+    // Shop on a budget, and barter.
+    let (count, left) = ford.budget_breakdown("Iron", dec!(1000))?;
+    println!(
+        "{:<40} => {count} Iron, {left} left",
+        "1000 Credits of Iron"
+    );
+    let (count, left) = ford.exchange(quantity, "Silver", "Iron")?;
+    println!("{:<40} => {count} Iron, {left} left", "42 Silver in Iron");
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Numerals in a simplified ASCII apostrophus notation, for values beyond the range of `Roman`.
+//!
+//! Apostrophus writes large numbers with a stroke `I` enclosed by reversed Cs, which are written
+//! as `)` here. Every additional `)` multiplies the value by ten, and a matching number of `C`s in
+//! front doubles it:
+//!
+//! | Symbol    | Value   |
+//! |-----------|---------|
+//! | `I)`      | 500     |
+//! | `CI)`     | 1000    |
+//! | `I))`     | 5000    |
+//! | `CCI))`   | 10000   |
+//! | `I)))`    | 50000   |
+//! | `CCCI)))` | 100000  |
+//!
+//! Hundreds and above are written additively, e.g. 900 is `I)CCCC`,
+//! while values below 100 are written like standard roman numerals.
+//! Only the feature `apostrophus` enables this module.
+use std::fmt;
+
+use crate::error::InvalidRomanNumeral;
+use crate::numeral::Numeral;
+
+/// The symbols and their values, largest first.
+const SYMBOLS: [(u32, &str); 15] = [
+    (100_000, "CCCI)))"),
+    (50_000, "I)))"),
+    (10_000, "CCI))"),
+    (5_000, "I))"),
+    (1_000, "CI)"),
+    (500, "I)"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// `Apostrophus` represents all valid numerals in the apostrophus notation, see the module docs.
+///
+/// An `Apostrophus` can be constructed using the `TryFrom` trait, by providing a `&str`
+/// or a `u32` between 1 and `Apostrophus::MAX`. Invalid numerals cannot be constructed.
+///
+/// # Examples
+/// ```
+/// use intra::apostrophus::Apostrophus;
+/// let numeral = Apostrophus::try_from("CCI))I))CI)").unwrap();
+/// assert_eq!(u32::from(numeral), 16000);
+/// assert_eq!(Apostrophus::try_from(1500u32).unwrap().to_string(), "CI)I)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Apostrophus {
+    value: u32,
+}
+
+impl Apostrophus {
+    /// The largest value that can be written, 399999.
+    pub const MAX: u32 = 399_999;
+
+    /// Splits a numeral into the values of its symbols, preferring the longest symbol.
+    fn symbols(text: &str) -> Option<Vec<u32>> {
+        let mut values = Vec::new();
+        let mut rest = text;
+
+        while !rest.is_empty() {
+            let (value, symbol) = SYMBOLS
+                .iter()
+                .filter(|(_, symbol)| rest.starts_with(symbol))
+                .max_by_key(|(_, symbol)| symbol.len())?;
+            values.push(*value);
+            rest = &rest[symbol.len()..];
+        }
+
+        Some(values)
+    }
+}
+
+impl fmt::Display for Apostrophus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut value = self.value;
+        for (symbol_value, symbol) in SYMBOLS {
+            while value >= symbol_value {
+                f.write_str(symbol)?;
+                value -= symbol_value;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Apostrophus {
+    type Error = InvalidRomanNumeral;
+
+    /// Parses a numeral, ignoring leading and trailing ASCII whitespace.
+    ///
+    /// Symbols must be in descending order and written as `Display` writes them,
+    /// so `CCI)` (1100) is invalid, as it's written `CI)C`.
+    fn try_from(text: &'a str) -> Result<Self, InvalidRomanNumeral> {
+        let text = text.trim_matches(|c: char| c.is_ascii_whitespace());
+        let symbols = Apostrophus::symbols(text).ok_or(InvalidRomanNumeral)?;
+
+        let value = symbols
+            .iter()
+            .try_fold(0u32, |total, &value| total.checked_add(value))
+            .ok_or(InvalidRomanNumeral)?;
+        let numeral = Apostrophus::try_from(value)?;
+
+        // only the canonical spelling of a value is valid
+        if numeral.to_string() == text {
+            Ok(numeral)
+        } else {
+            Err(InvalidRomanNumeral)
+        }
+    }
+}
+
+impl TryFrom<u32> for Apostrophus {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(value: u32) -> Result<Self, InvalidRomanNumeral> {
+        if (1..=Apostrophus::MAX).contains(&value) {
+            Ok(Self { value })
+        } else {
+            Err(InvalidRomanNumeral)
+        }
+    }
+}
+
+impl From<Apostrophus> for u32 {
+    fn from(numeral: Apostrophus) -> Self {
+        numeral.value
+    }
+}
+
+impl Numeral for Apostrophus {
+    fn to_u32(&self) -> u32 {
+        self.value
+    }
+
+    fn try_from_u32(value: u32) -> Option<Self> {
+        Apostrophus::try_from(value).ok()
+    }
+
+    fn try_from_str(text: &str) -> Option<Self> {
+        Apostrophus::try_from(text).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let numerals = [
+            (42, "XLII"),
+            (500, "I)"),
+            (1_999, "CI)I)CCCCXCIX"),
+            (5_000, "I))"),
+            (16_000, "CCI))I))CI)"),
+            (250_042, "CCCI)))CCCI)))I)))XLII"),
+            (
+                Apostrophus::MAX,
+                "CCCI)))CCCI)))CCCI)))I)))CCI))CCI))CCI))CCI))I))CI)CI)CI)CI)I)CCCCXCIX",
+            ),
+        ];
+
+        for (value, text) in numerals {
+            let numeral = Apostrophus::try_from(value).unwrap();
+            assert_eq!(numeral.to_string(), text);
+            assert_eq!(Apostrophus::try_from(text).unwrap(), numeral);
+            assert_eq!(u32::from(numeral), value);
+        }
+
+        for value in (1..=Apostrophus::MAX).step_by(997) {
+            let text = Apostrophus::try_from(value).unwrap().to_string();
+            assert_eq!(Apostrophus::try_from_str(&text).unwrap().to_u32(), value);
+        }
+    }
+
+    #[test]
+    fn validation() {
+        // positive tests
+        assert!(Apostrophus::is_valid(" I)) "));
+        assert!(Apostrophus::is_valid("CI)C"));
+
+        // negative tests
+        assert!(!Apostrophus::is_valid(""));
+        assert!(!Apostrophus::is_valid("I)I)"));
+        assert!(!Apostrophus::is_valid("CCI)"));
+        assert!(!Apostrophus::is_valid("CI)CI)CI)CI)CI)"));
+        assert!(!Apostrophus::is_valid("IIII"));
+        assert!(!Apostrophus::is_valid("I)))))"));
+        assert!(!Apostrophus::is_valid("M"));
+        assert!(Apostrophus::try_from(0u32).is_err());
+        assert!(Apostrophus::try_from(Apostrophus::MAX + 1).is_err());
+    }
+}
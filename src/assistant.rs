@@ -1,21 +1,377 @@
 //! A personal assistant for all your galaxy hitchhiking needs.
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
 
 use hashbrown::{HashMap, HashSet};
 use lazy_static::lazy_static;
 use regex::Regex;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 
-use crate::error::QueryError;
+use crate::credits::Credits;
+use crate::error::{InvalidRomanNumeral, QueryError, ValidationIssue};
 use crate::language::Language;
+use crate::quantity::Quantity;
+use crate::roman::Roman;
 
 lazy_static! {
-    static ref QUERY_SET_DIGIT: Regex = Regex::new(r"([a-z]+)\s+(?i:is)\s+([IVXLCDM])").unwrap();
+    static ref QUERY_SET_DIGIT: Regex = Regex::new(r"^\s*([a-z]+)\s+(?i:is)\s+([IVXLCDM])\s*$").unwrap();
+    static ref QUERY_FORGET_WORD: Regex =
+        Regex::new(r"^\s*(?:(?i:forget)\s+([a-z]+)|([a-z]+)\s+(?i:is)\s+(?i:unknown|nothing))\s*$")
+            .unwrap();
+    static ref QUERY_CLEAR_PRICES: Regex =
+        Regex::new(r"^\s*(?i:forget\s+all\s+prices)\s*$").unwrap();
     static ref QUERY_SET_ITEM: Regex =
-        Regex::new(r"([a-z\s]*)\s+([A-Z].*)\s+(?i:is)\s+([0-9]+)\s+(?i:credits)").unwrap();
-    static ref QUERY_NUMERAL: Regex = Regex::new(r"(?i:how\s+much\s+is\s+)([a-z\s]*)\?").unwrap();
-    static ref QUERY_PRICE: Regex =
-        Regex::new(r"(?i:how\s+many\s+credits\s+is\s+)([a-z\s]*)\s+([A-Z].*)\s*\?").unwrap();
+        Regex::new(r"([a-z\s]*)\s+([A-Z].*)\s+(?i:is)\s+(-?[0-9]+)\s+(?i:credits)").unwrap();
+    static ref QUERY_NUMERAL: Regex =
+        Regex::new(r"(?i:how\s+much\s+is\s+)([a-z\s]*|\s*[0-9]+\s*)\?").unwrap();
+    static ref QUERY_PRICE: Regex = Regex::new(
+        r"(?i:how\s+many\s+credits\s+is\s+|what(?:\s+is|'s)\s+the\s+price\s+of\s+)([a-z\s]*|\s*[0-9]+)\s+([A-Z].*)\s*\?"
+    )
+    .unwrap();
+    static ref QUERY_PRICE_INTERGALACTIC: Regex = Regex::new(
+        r"^\s*(?i:how\s+many\s+credits\s+is\s+)([a-z\s]*|\s*[0-9]+)\s+([A-Z].*?)\s+(?i:in\s+intergalactic)\s*\?\s*$"
+    )
+    .unwrap();
+    static ref QUERY_PRICE_LIST: Regex =
+        Regex::new(r"^\s*(?i:how\s+many\s+credits\s+(?:is|are)\s+)(.*?)\s*\?\s*$").unwrap();
+    static ref LIST_SEPARATOR: Regex = Regex::new(r"\s*,\s*(?:and\s+)?|\s+and\s+").unwrap();
+    static ref LIST_ENTRY: Regex =
+        Regex::new(r"^([a-z]+(?:\s+[a-z]+)*|[0-9]+)\s+([A-Z].*)$").unwrap();
+    static ref QUERY_PRICE_IN: Regex = Regex::new(
+        r"(?i:how\s+many\s+)([a-zA-Z]+)\s+(?i:is\s+)([a-z\s]*|\s*[0-9]+)\s+([A-Z].*)\s*\?"
+    )
+    .unwrap();
+    static ref QUERY_UNIT_PRICE: Regex = Regex::new(
+        r"^\s*(?i:how\s+many\s+credits\s+is\s+)([A-Z].*?)\s+(?i:per)(?:\s+(?i:unit))?\s*\?\s*$"
+    )
+    .unwrap();
+    static ref QUERY_RENAME_ITEM: Regex =
+        Regex::new(r"^\s*(?i:rename)\s+([A-Z].*?)\s+(?i:to)\s+([A-Z].*?)\s*$").unwrap();
+    static ref QUERY_RENAME_WORD: Regex =
+        Regex::new(r"^\s*(?i:rename)\s+([a-z]+)\s+(?i:to)\s+([a-z]+)\s*$").unwrap();
+    static ref QUERY_WORD: Regex = Regex::new(r"^\s*(?i:what\s+is\s+)([a-z]+)\s*\?\s*$").unwrap();
+    static ref QUERY_INTERGALACTIC: Regex =
+        Regex::new(r"^\s*(?i:what\s+is\s+)([0-9]+)\s+(?i:in\s+intergalactic)\s*\?\s*$").unwrap();
+    static ref QUERY_ITEMS: Regex =
+        Regex::new(r"^\s*(?i:what\s+items\s+do\s+you\s+know)\s*\?\s*$").unwrap();
+    static ref QUERY_AVERAGE_PRICE: Regex =
+        Regex::new(r"^\s*(?i:what\s+is\s+the\s+average\s+price)\s*\?\s*$").unwrap();
+    static ref QUERY_EXCHANGE: Regex = Regex::new(
+        r"^\s*(?i:how\s+many\s+)([A-Z].*?)\s+(?i:equals?)\s+([a-z\s]*|\s*[0-9]+)\s+([A-Z].*?)\s*\?\s*$"
+    )
+    .unwrap();
+    static ref QUERY_PRICE_IN_ITEM: Regex = Regex::new(
+        r"^\s*(?i:how\s+much\s+is\s+)([a-z\s]*|\s*[0-9]+)\s+([A-Z].*?)\s+(?i:in)\s+([A-Z].*?)\s*\?\s*$"
+    )
+    .unwrap();
+    static ref QUERY_LOWERCASE_PRICE: Regex = Regex::new(
+        r"^\s*(?i:how\s+many\s+credits\s+is\s+|what(?:\s+is|'s)\s+the\s+price\s+of\s+)([a-z]+(?:\s+[a-z]+)*)\s*\?\s*$"
+    )
+    .unwrap();
+    static ref QUERY_LOWERCASE_SET_ITEM: Regex =
+        Regex::new(r"^\s*([a-z]+(?:\s+[a-z]+)*)\s+(?i:is)\s+-?[0-9]+\s+(?i:credits)\s*$").unwrap();
+    static ref QUERY_BUDGET: Regex = Regex::new(
+        r"^\s*(?i:how\s+many\s+)([A-Z].*?)\s+(?i:for)\s+([0-9]+(?:\.[0-9]+)?)\s+(?i:credits)\s*\?\s*$"
+    )
+    .unwrap();
+}
+
+/// Parses a captured amount of credits.
+fn parse_price(credits: &str) -> Result<Decimal, QueryError> {
+    Decimal::from_str_exact(credits).map_err(|_| QueryError::InvalidPrice(credits.to_string()))
+}
+
+/// Normalizes the whitespace in a captured item name.
+///
+/// An item name starts at the first capitalized word and spans everything up to the keyword
+/// that follows it, so `glob glob White Gold` and `glob Soy milk` are the items `White Gold`
+/// and `Soy milk`.
+fn item_name(capture: &str) -> String {
+    capture.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits a list of amounts of items, such as `glob Gold, glob glob Silver and pish Iron`.
+///
+/// Returns `None` unless the list has at least two entries, and every entry is an amount
+/// followed by an item.
+fn price_list(list: &str) -> Option<Vec<(&str, String)>> {
+    let entries = LIST_SEPARATOR
+        .split(list.trim())
+        .map(|entry| {
+            let captures = LIST_ENTRY.captures(entry)?;
+            let amount = captures.get(1).unwrap().as_str();
+            Some((amount, item_name(captures.get(2).unwrap().as_str())))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    (entries.len() > 1).then_some(entries)
+}
+
+/// Joins phrases into an English list, e.g. `a, b and c`.
+fn join_list(phrases: &[String]) -> String {
+    match phrases {
+        [] => String::new(),
+        [phrase] => phrase.clone(),
+        [init @ .., last] => format!("{} and {last}", init.join(", ")),
+    }
+}
+
+/// Divides the total price of some amount of an item into the price of a single unit.
+///
+/// This is the guard that keeps zero amounts out of the price set.
+fn unit_price_of(total: Decimal, count: Decimal) -> Result<Decimal, QueryError> {
+    if count.is_zero() {
+        return Err(QueryError::ZeroQuantity);
+    }
+
+    let price = total.checked_div(count).ok_or(QueryError::PriceOverflow)?;
+
+    if price.is_zero() && !total.is_zero() {
+        return Err(QueryError::PriceUnderflow);
+    }
+
+    Ok(price)
+}
+
+/// The price of an item, kept as the total for the amount it was defined with.
+///
+/// Dividing the total by the amount may not be exact, e.g. 10 credits for 3 units,
+/// so totals are computed from the defined total to avoid losing precision.
+/// The amount is never zero, it's either `Decimal::ONE` or checked by `unit_price_of`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Price {
+    total: Decimal,
+    count: Decimal,
+}
+
+impl Price {
+    /// The price of a single unit, which may be rounded.
+    fn unit(self) -> Decimal {
+        self.total / self.count
+    }
+
+    /// The total price of `count` units.
+    fn of(self, count: Decimal) -> Result<Decimal, QueryError> {
+        if self.count.is_zero() {
+            return Err(QueryError::ZeroQuantity);
+        }
+
+        match self.total.checked_mul(count) {
+            // multiply first, so multiples of the defined amount are exact
+            Some(total) => total
+                .checked_div(self.count)
+                .ok_or(QueryError::PriceOverflow),
+            None => self
+                .unit()
+                .checked_mul(count)
+                .ok_or(QueryError::PriceOverflow),
+        }
+    }
+
+    /// How many units can be bought with a budget, if the price is positive.
+    fn affordable(self, budget: Decimal) -> Option<Decimal> {
+        match budget.checked_mul(self.count) {
+            Some(budget) => budget.checked_div(self.total),
+            None => budget.checked_div(self.unit()),
+        }
+        .map(|count| count.floor())
+    }
+}
+
+/// A state change made by a query, recorded so it can be undone.
+#[derive(Debug, Clone)]
+enum Change<'a> {
+    Word(Cow<'a, str>, char),
+    WordForgotten(Cow<'a, str>, char),
+    Item(Cow<'a, str>),
+    PriceUpdated(Cow<'a, str>, Price),
+    PricesCleared(HashMap<Cow<'a, str>, Price>),
+    ItemRenamed(Cow<'a, str>, Cow<'a, str>),
+    WordRenamed(Cow<'a, str>, Cow<'a, str>, char),
+}
+
+/// The forms of queries understood by `Ford`.
+///
+/// `QueryKind` displays as a stable kebab-case name such as `set-digit`, suitable for logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryKind {
+    /// `<word> is <roman digit>`
+    SetDigit,
+    /// `Forget <word>` or `<word> is unknown`
+    ForgetWord,
+    /// `Forget all prices`
+    ClearPrices,
+    /// `<number> <Item> is <decimal> credits`
+    SetItem,
+    /// `How much is <number>?`
+    AskNumber,
+    /// `How many credits is <number> <Item>?` or `What is the price of <number> <Item>?`
+    AskPrice,
+    /// `How many credits is <number> <Item> in intergalactic?`
+    AskPriceIntergalactic,
+    /// `How many credits is <number> <Item> and <number> <Item>?`
+    AskTotalPrice,
+    /// `How many <currency> is <number> <Item>?`
+    AskPriceIn,
+    /// `How many credits is <Item> per unit?`
+    AskUnitPrice,
+    /// `Rename <Item> to <Item>`
+    RenameItem,
+    /// `Rename <word> to <word>`
+    RenameWord,
+    /// `How many <Item> for <decimal> credits?`
+    AskBudget,
+    /// `How many <Item> equals <number> <Item>?`
+    AskExchange,
+    /// `How much is <number> <Item> in <Item>?`
+    AskPriceInItem,
+    /// `What is <word>?`
+    AskWord,
+    /// `What is <decimal> in intergalactic?`
+    AskIntergalactic,
+    /// `What items do you know?`
+    ListItems,
+    /// `What is the average price?`
+    AskAveragePrice,
+}
+
+impl QueryKind {
+    /// Checks if queries of this form change what `Ford` knows, instead of asking a question.
+    pub fn is_definition(self) -> bool {
+        matches!(
+            self,
+            Self::SetDigit
+                | Self::ForgetWord
+                | Self::ClearPrices
+                | Self::SetItem
+                | Self::RenameItem
+                | Self::RenameWord
+        )
+    }
+}
+
+impl fmt::Display for QueryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::SetDigit => "set-digit",
+            Self::ForgetWord => "forget-word",
+            Self::ClearPrices => "clear-prices",
+            Self::SetItem => "set-item",
+            Self::AskNumber => "ask-number",
+            Self::AskPrice => "ask-price",
+            Self::AskPriceIntergalactic => "ask-price-intergalactic",
+            Self::AskTotalPrice => "ask-total-price",
+            Self::AskPriceIn => "ask-price-in",
+            Self::AskUnitPrice => "ask-unit-price",
+            Self::RenameItem => "rename-item",
+            Self::RenameWord => "rename-word",
+            Self::AskBudget => "ask-budget",
+            Self::AskExchange => "ask-exchange",
+            Self::AskPriceInItem => "ask-price-in-item",
+            Self::AskWord => "ask-word",
+            Self::AskIntergalactic => "ask-intergalactic",
+            Self::ListItems => "list-items",
+            Self::AskAveragePrice => "ask-average-price",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How credit totals are rounded before they're printed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Keep every decimal place, but remove trailing zeros.
+    #[default]
+    Normalize,
+    /// Round to a fixed number of decimal places.
+    DecimalPlaces(u32, RoundingStrategy),
+}
+
+impl Rounding {
+    fn apply(self, value: Decimal) -> Decimal {
+        match self {
+            Self::Normalize => value.normalize(),
+            Self::DecimalPlaces(dp, strategy) => value.round_dp_with_strategy(dp, strategy),
+        }
+    }
+}
+
+/// How credit amounts are formatted in answers.
+///
+/// The default formats `57800` as `57800 Credits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceFormat {
+    /// Separator between groups of thousands, e.g. `,` for `57,800`.
+    pub grouping: Option<char>,
+    /// Text in front of the amount, e.g. `₡` for `₡57800`.
+    pub prefix: String,
+    /// Text after the amount.
+    pub suffix: String,
+}
+
+impl Default for PriceFormat {
+    fn default() -> Self {
+        Self {
+            grouping: None,
+            prefix: String::new(),
+            suffix: " Credits".to_string(),
+        }
+    }
+}
+
+impl PriceFormat {
+    /// Formats an amount of credits.
+    pub fn format(&self, value: Decimal) -> String {
+        let amount = value.to_string();
+
+        let amount = match self.grouping {
+            Some(separator) => {
+                let (sign, unsigned) = match amount.strip_prefix('-') {
+                    Some(unsigned) => ("-", unsigned),
+                    None => ("", amount.as_str()),
+                };
+                let (integer, fraction) = match unsigned.find('.') {
+                    Some(index) => unsigned.split_at(index),
+                    None => (unsigned, ""),
+                };
+
+                let mut grouped = String::new();
+                for (index, digit) in integer.chars().enumerate() {
+                    if index > 0 && (integer.len() - index) % 3 == 0 {
+                        grouped.push(separator);
+                    }
+                    grouped.push(digit);
+                }
+
+                format!("{sign}{grouped}{fraction}")
+            }
+            None => amount,
+        };
+
+        format!("{}{amount}{}", self.prefix, self.suffix)
+    }
+}
+
+/// A typed fact for `Ford::from_facts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fact<'a> {
+    /// `<word> is <roman digit>`
+    Word {
+        #[allow(missing_docs)]
+        word: Cow<'a, str>,
+        #[allow(missing_docs)]
+        digit: char,
+    },
+    /// `<phrase> <item> is <total> credits`
+    Item {
+        #[allow(missing_docs)]
+        phrase: Cow<'a, str>,
+        #[allow(missing_docs)]
+        item: Cow<'a, str>,
+        #[allow(missing_docs)]
+        total: Decimal,
+    },
 }
 
 /// Fast Omniscient Robotic guiDe is a personal assistant on your hitchhike through the galaxy.
@@ -23,7 +379,17 @@ lazy_static! {
 pub struct Ford<'a> {
     language: Language<'a>,
     known_digits: HashSet<char>,
-    price_set: HashMap<Cow<'a, str>, Decimal>,
+    price_set: HashMap<Cow<'a, str>, Price>,
+    history: Vec<Change<'a>>,
+    show_roman: bool,
+    rounding: Rounding,
+    price_format: PriceFormat,
+    price_tolerance: Decimal,
+    verbose: bool,
+    show_unit_price: bool,
+    skip_unknown_items: bool,
+    allow_price_updates: bool,
+    currencies: HashMap<String, (String, Decimal)>,
 }
 
 impl<'a> Ford<'a> {
@@ -35,10 +401,658 @@ impl<'a> Ford<'a> {
     /// Constructs a new `Ford` with a `Language` and a set of prices.
     pub fn with(language: Language<'a>, price_set: HashMap<Cow<'a, str>, Decimal>) -> Self {
         let known_digits = language.known_digits().collect();
+        let price_set = price_set
+            .into_iter()
+            .map(|(item, price)| {
+                let price = Price {
+                    total: price,
+                    count: Decimal::ONE,
+                };
+                (item, price)
+            })
+            .collect();
         Self {
             language,
             known_digits,
             price_set,
+            history: Vec::new(),
+            show_roman: false,
+            rounding: Rounding::default(),
+            price_format: PriceFormat::default(),
+            price_tolerance: Decimal::ZERO,
+            verbose: false,
+            show_unit_price: false,
+            skip_unknown_items: false,
+            allow_price_updates: false,
+            currencies: HashMap::new(),
+        }
+    }
+
+    /// Returns the `Language` of known words.
+    pub fn language(&self) -> &Language<'a> {
+        &self.language
+    }
+
+    /// Sets whether number answers also show the roman numeral, e.g. `glob glob is 2 (II)`.
+    pub fn set_show_roman(&mut self, show_roman: bool) {
+        self.show_roman = show_roman;
+    }
+
+    /// Sets whether definitions are acknowledged with an answer, e.g. `OK: glob = I`.
+    ///
+    /// By default definitions are silent and `query` answers them with `None`.
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    /// Returns the acknowledgment of a definition, if verbose.
+    fn acknowledge(&self, definition: fmt::Arguments<'_>) -> Option<String> {
+        self.verbose.then(|| format!("OK: {definition}"))
+    }
+
+    /// Sets whether price answers also show the price of a single unit,
+    /// e.g. `glob glob Gold is 20 Credits (10 Credits each)`.
+    pub fn set_show_unit_price(&mut self, show_unit_price: bool) {
+        self.show_unit_price = show_unit_price;
+    }
+
+    /// Sets whether questions about several items skip unknown items instead of failing,
+    /// e.g. `glob Gold is 10 Credits (unknown: glob Copper)`.
+    ///
+    /// The question still fails if none of its items are known.
+    pub fn set_skip_unknown_items(&mut self, skip_unknown_items: bool) {
+        self.skip_unknown_items = skip_unknown_items;
+    }
+
+    /// Sets whether restating a known item replaces its price, e.g. when prices fluctuate.
+    ///
+    /// By default a restatement must agree with the known price, see `define_item`.
+    pub fn set_allow_price_updates(&mut self, allow_price_updates: bool) {
+        self.allow_price_updates = allow_price_updates;
+    }
+
+    /// Constructs a new `Ford` by running every line of a script through `query`.
+    ///
+    /// Blank lines are skipped and answers are discarded.
+    /// If any line fails, all errors are returned together with their 1-based line numbers.
+    pub fn train(script: &str) -> Result<Self, Vec<(usize, QueryError)>> {
+        let mut ford = Self::new();
+
+        let errors = script
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .filter_map(|(index, line)| ford.query(line).err().map(|err| (index + 1, err)))
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(ford)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Constructs a new `Ford` from typed facts, the typed equivalent of `train`.
+    ///
+    /// If any fact fails, the errors are returned together with the 0-based index of the fact.
+    /// With `fail_fast` only the first error is returned and later facts are ignored,
+    /// otherwise all errors are collected.
+    pub fn from_facts<I>(facts: I, fail_fast: bool) -> Result<Self, Vec<(usize, QueryError)>>
+    where
+        I: IntoIterator<Item = Fact<'a>>,
+    {
+        let mut ford = Self::new();
+        let mut errors = Vec::new();
+
+        for (index, fact) in facts.into_iter().enumerate() {
+            let result = match fact {
+                Fact::Word { word, digit } => ford.define_word(word, digit),
+                Fact::Item {
+                    phrase,
+                    item,
+                    total,
+                } => ford.define_item(&phrase, item, total),
+            };
+
+            if let Err(err) = result {
+                errors.push((index, err));
+                if fail_fast {
+                    break;
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(ford)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Define a new intergalactic word for a roman digit.
+    ///
+    /// This is the typed equivalent of the query `<word> is <roman digit>`.
+    pub fn define_word<S>(&mut self, word: S, digit: char) -> Result<(), QueryError>
+    where
+        Cow<'a, str>: From<S>,
+    {
+        let word: Cow<'a, str> = From::from(word);
+
+        if self.language.contains(&word) {
+            return Err(QueryError::WordAlreadyExists(word.into_owned()));
+        }
+
+        if self.known_digits.contains(&digit) {
+            return Err(QueryError::DigitAlreadyExists(digit));
+        }
+
+        Roman::digit_value(digit).ok_or(InvalidRomanNumeral)?;
+
+        self.language.insert::<Cow<'a, str>>(word.clone(), digit);
+        self.known_digits.insert(digit);
+        self.history.push(Change::Word(word, digit));
+
+        Ok(())
+    }
+
+    /// Forget an intergalactic word, freeing its digit for another word.
+    ///
+    /// Prices defined with the word are kept. Returns the digit of the word.
+    pub fn forget_word(&mut self, word: &str) -> Result<char, QueryError> {
+        let digit = self
+            .language
+            .remove(word)
+            .ok_or_else(|| QueryError::UnrecognizedWord(word.to_string()))?;
+
+        if !self.language.known_digits().any(|known| known == digit) {
+            self.known_digits.remove(&digit);
+        }
+        self.history
+            .push(Change::WordForgotten(Cow::Owned(word.to_string()), digit));
+
+        Ok(digit)
+    }
+
+    /// Forget the prices of all items, keeping the known words.
+    pub fn clear_prices(&mut self) {
+        let prices = std::mem::take(&mut self.price_set);
+        self.history.push(Change::PricesCleared(prices));
+    }
+
+    /// Define the price of an item, given the total price for an intergalactic amount of it.
+    ///
+    /// This is the typed equivalent of the query `<number> <Item> is <decimal> credits`.
+    /// A known item may be restated with a different amount, as long as the implied unit price
+    /// doesn't differ from the known one by more than the price tolerance.
+    /// Otherwise the restatement is an `InconsistentPrice` error,
+    /// unless price updates are allowed and the restated price replaces the known one.
+    pub fn define_item<S>(
+        &mut self,
+        intergalactic: &str,
+        item: S,
+        total: impl Into<Credits>,
+    ) -> Result<(), QueryError>
+    where
+        Cow<'a, str>: From<S>,
+    {
+        let total = total.into().amount();
+        let roman = self.language.translate(intergalactic)?;
+        let count = Decimal::from(u32::from(roman));
+
+        let item: Cow<'a, str> = From::from(item);
+
+        let item_price = unit_price_of(total, count)?;
+
+        if self.allow_price_updates {
+            // restating the same unit price changes nothing, and isn't recorded
+            if self
+                .price_set
+                .get(&item)
+                .is_some_and(|known_price| known_price.unit() == item_price)
+            {
+                return Ok(());
+            }
+
+            let price = Price { total, count };
+            if let Some(known_price) = self.price_set.insert(item.clone(), price) {
+                self.history.push(Change::PriceUpdated(item, known_price));
+            } else {
+                self.history.push(Change::Item(item));
+            }
+            return Ok(());
+        }
+
+        if let Some(known_price) = self.price_set.get(&item) {
+            // a restatement must agree with the known unit price, which is kept as is
+            // a difference too large for a `Decimal` is certainly inconsistent
+            let consistent = item_price
+                .checked_sub(known_price.unit())
+                .is_some_and(|difference| difference.abs() <= self.price_tolerance);
+            if !consistent {
+                return Err(QueryError::InconsistentPrice(item.into_owned()));
+            }
+            return Ok(());
+        }
+
+        self.price_set.insert(item.clone(), Price { total, count });
+        self.history.push(Change::Item(item));
+
+        Ok(())
+    }
+
+    /// Sets how credit totals are rounded. Defaults to `Rounding::Normalize`.
+    pub fn set_rounding(&mut self, rounding: Rounding) {
+        self.rounding = rounding;
+    }
+
+    /// Sets how credit amounts are formatted in answers.
+    pub fn set_price_format(&mut self, price_format: PriceFormat) {
+        self.price_format = price_format;
+    }
+
+    /// Rounds and formats an amount of credits for an answer.
+    fn format_credits(&self, value: impl Into<Credits>) -> String {
+        self.price_format
+            .format(self.rounding.apply(value.into().amount()))
+    }
+
+    /// Defines a currency that prices can be asked in, worth `rate` units per credit.
+    ///
+    /// Prices are still kept in credits, and only converted for answers.
+    /// Currency names are case-insensitive, redefining a currency replaces its rate.
+    pub fn define_currency(&mut self, name: &str, rate: Decimal) -> Result<(), QueryError> {
+        if rate <= Decimal::ZERO {
+            return Err(QueryError::NonPositiveRate(name.to_string()));
+        }
+
+        self.currencies
+            .insert(name.to_lowercase(), (name.to_string(), rate));
+        Ok(())
+    }
+
+    /// Converts an amount of credits to a currency, and formats it like credits.
+    fn format_currency(&self, credits: Decimal, currency: &str) -> Result<String, QueryError> {
+        let (name, rate) = self
+            .currencies
+            .get(&currency.to_lowercase())
+            .ok_or_else(|| QueryError::UnrecognizedCurrency(currency.to_string()))?;
+
+        let value = credits
+            .checked_mul(*rate)
+            .ok_or(QueryError::PriceOverflow)?;
+        let format = PriceFormat {
+            prefix: String::new(),
+            suffix: format!(" {name}"),
+            ..self.price_format.clone()
+        };
+        Ok(format.format(self.rounding.apply(value)))
+    }
+
+    /// Sets how much a restated unit price may differ from the known one before
+    /// it's considered inconsistent. Defaults to zero.
+    pub fn set_price_tolerance(&mut self, tolerance: impl Into<Credits>) {
+        self.price_tolerance = tolerance.into().amount();
+    }
+
+    /// Returns an iterator over all known items and the prices of a single unit.
+    pub fn items(&self) -> impl Iterator<Item = (&str, Credits)> + '_ {
+        self.price_set
+            .iter()
+            .map(|(item, price)| (item.as_ref(), Credits(price.unit())))
+    }
+
+    /// Returns the mean unit price of all known items.
+    ///
+    /// Returns `None` if no items are known, or if the sum of their prices overflows.
+    pub fn average_price(&self) -> Option<Credits> {
+        if self.price_set.is_empty() {
+            return None;
+        }
+
+        let sum = self
+            .items()
+            .try_fold(Credits::ZERO, |sum, (_, price)| sum.checked_add(price))?;
+        sum.amount()
+            .checked_div(Decimal::from(self.price_set.len()))
+            .map(Credits)
+    }
+
+    /// Checks the known words and prices, returning every problem found.
+    ///
+    /// Words must map to distinct roman digits, and every digit below the largest one must
+    /// have a word. Prices must not be negative, and the amount an item was defined with must
+    /// be expressible with the known words.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues = Vec::new();
+
+        let mut words_of = BTreeMap::<char, Vec<String>>::new();
+        for (word, digit) in self.language.to_sorted_vec() {
+            if Roman::digit_value(digit).is_none() {
+                issues.push(ValidationIssue::InvalidDigit(word.to_string(), digit));
+            } else {
+                words_of.entry(digit).or_default().push(word.to_string());
+            }
+        }
+
+        let mut digits = ['I', 'V', 'X', 'L', 'C', 'D', 'M'].to_vec();
+        while digits
+            .last()
+            .is_some_and(|digit| !words_of.contains_key(digit))
+        {
+            digits.pop();
+        }
+        for digit in digits {
+            match words_of.remove(&digit) {
+                None => issues.push(ValidationIssue::MissingDigit(digit)),
+                Some(words) if words.len() > 1 => {
+                    issues.push(ValidationIssue::DuplicateDigit(digit, words))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut items = self.price_set.iter().collect::<Vec<_>>();
+        items.sort_unstable_by_key(|&(item, _)| item);
+        for (item, price) in items {
+            if price.total < Decimal::ZERO {
+                issues.push(ValidationIssue::NegativePrice(item.to_string()));
+            }
+
+            let translatable = u32::try_from(price.count)
+                .ok()
+                .and_then(|count| Roman::try_from(count).ok())
+                .is_some_and(|roman| self.language.to_intergalactic(&roman).is_ok());
+            if !translatable {
+                issues.push(ValidationIssue::UntranslatableAmount(item.to_string()));
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Exports the known words and items as a script, which `train` turns back into
+    /// an equivalent `Ford`.
+    ///
+    /// Words come first, sorted by word, followed by items sorted by name.
+    /// Queries only state whole credit totals, so every item is stated with the amount it was
+    /// defined with if possible, or otherwise the smallest amount the known words can express
+    /// that costs a whole number of credits. Items without such an amount are left out.
+    pub fn to_script(&self) -> String {
+        let mut script = String::new();
+
+        for (word, digit) in self.language.to_sorted_vec() {
+            script.push_str(&format!("{word} is {digit}\n"));
+        }
+
+        let mut items = self.price_set.iter().collect::<Vec<_>>();
+        items.sort_unstable_by_key(|&(item, _)| item);
+
+        for (item, price) in items {
+            let defined = u32::try_from(price.count)
+                .ok()
+                .and_then(|count| Roman::try_from(count).ok());
+            let statement = defined.into_iter().chain(Roman::all()).find_map(|roman| {
+                let total = price.of(Decimal::from(u32::from(&roman))).ok()?;
+                if !total.fract().is_zero() {
+                    return None;
+                }
+                let amount = self.language.to_intergalactic(&roman).ok()?;
+                Some(format!(
+                    "{amount} {item} is {} Credits\n",
+                    total.normalize()
+                ))
+            });
+
+            if let Some(statement) = statement {
+                script.push_str(&statement);
+            }
+        }
+
+        script
+    }
+
+    /// Translates the amount in a question, which is either an intergalactic numeral
+    /// or a plain decimal integer. The `Roman` is `None` if the integer is out of its range.
+    ///
+    /// Fails with `QueryError::ZeroQuantity` for the integer 0.
+    fn quantity(&self, amount: &str) -> Result<(Quantity, Option<Roman>), QueryError> {
+        if !amount.is_empty() && amount.chars().all(|c| c.is_ascii_digit()) {
+            let value = amount
+                .parse::<u32>()
+                .map_err(|_| QueryError::InvalidQuantity(amount.to_string()))?;
+            if value == 0 {
+                return Err(QueryError::ZeroQuantity);
+            }
+            Ok((Quantity(value), Roman::try_from(value).ok()))
+        } else {
+            let roman = self.language.translate(amount)?;
+            Ok((Quantity::from(&roman), Some(roman)))
+        }
+    }
+
+    /// Looks up the price of an item.
+    fn price(&self, item: &str) -> Result<Price, QueryError> {
+        if self.price_set.is_empty() {
+            return Err(QueryError::NoPricesDefined);
+        }
+
+        self.price_set
+            .get(item)
+            .copied()
+            .ok_or_else(|| QueryError::UnrecognizedItem(item.to_string()))
+    }
+
+    /// Looks up the price of a single unit of an item.
+    fn unit_price(&self, item: &str) -> Result<Decimal, QueryError> {
+        Ok(self.price(item)?.unit())
+    }
+
+    /// Computes the total price of an amount of an item.
+    fn total_price(&self, item: &str, count: Quantity) -> Result<Decimal, QueryError> {
+        self.price(item)?.of(Decimal::from(count))
+    }
+
+    /// Returns how many units of an item can be bought with a budget.
+    ///
+    /// Fails with `QueryError::InvalidPrice` if the budget is negative.
+    pub fn affordable(
+        &self,
+        item: &str,
+        budget: impl Into<Credits>,
+    ) -> Result<Quantity, QueryError> {
+        let budget = budget.into().amount();
+        if budget < Decimal::ZERO {
+            return Err(QueryError::InvalidPrice(budget.to_string()));
+        }
+
+        let price = self.price(item)?;
+
+        if price.unit() <= Decimal::ZERO {
+            return Err(QueryError::NonPositivePrice(item.to_string()));
+        }
+
+        let count = price.affordable(budget).ok_or(QueryError::PriceOverflow)?;
+        u32::try_from(count)
+            .map(Quantity)
+            .map_err(|_| QueryError::PriceOverflow)
+    }
+
+    /// Returns how many units of an item can be bought with a budget,
+    /// together with the credits left over after buying them.
+    pub fn budget_breakdown(
+        &self,
+        item: &str,
+        budget: impl Into<Credits>,
+    ) -> Result<(Quantity, Credits), QueryError> {
+        let budget = budget.into();
+        let count = self.affordable(item, budget)?;
+        let spent = Credits(self.total_price(item, count)?);
+
+        Ok((count, budget - spent))
+    }
+
+    /// Returns how many units of `unit` cost the same as `count` units of `item`,
+    /// together with the credits left over, e.g. for bartering.
+    ///
+    /// Fails with `QueryError::NonPositivePrice` unless `unit` has a positive price.
+    pub fn exchange(
+        &self,
+        count: Quantity,
+        item: &str,
+        unit: &str,
+    ) -> Result<(Quantity, Credits), QueryError> {
+        let total = self.total_price(item, count)?;
+        self.budget_breakdown(unit, total)
+    }
+
+    /// Writes a whole amount of credits as an intergalactic numeral.
+    ///
+    /// Fails with `QueryError::FractionalPrice` if the value isn't a whole number,
+    /// with `QueryError::InvalidRomanNumeral` unless it's between 1 and 3999,
+    /// and with `QueryError::MissingDigitWord` if a digit of its numeral has no word.
+    pub fn format_as_intergalactic(&self, value: impl Into<Credits>) -> Result<String, QueryError> {
+        let value = value.into().amount();
+        if !value.fract().is_zero() {
+            return Err(QueryError::FractionalPrice(value.normalize().to_string()));
+        }
+
+        let value = u32::try_from(value).map_err(|_| InvalidRomanNumeral)?;
+        let roman = Roman::try_from(value)?;
+        self.language.to_intergalactic(&roman)
+    }
+
+    /// Formats the result of `exchange`, e.g. `12 Silver and 2 Credits`.
+    fn format_exchange(&self, units: Quantity, unit: &str, left: Credits) -> String {
+        if left.amount().is_zero() {
+            format!("{units} {unit}")
+        } else {
+            format!("{units} {unit} and {}", self.format_credits(left))
+        }
+    }
+
+    /// Returns the grand total of a shopping list with one `<number> <Item>` per line.
+    ///
+    /// Blank lines are skipped. Errors are wrapped with the line that caused them,
+    /// see `QueryError::with_query`.
+    pub fn total_cost<'l, I>(&self, lines: I) -> Result<Credits, QueryError>
+    where
+        I: IntoIterator<Item = &'l str>,
+    {
+        lines
+            .into_iter()
+            .filter(|line| !line.trim().is_empty())
+            .try_fold(Credits::ZERO, |total, line| {
+                let price = self
+                    .line_cost(line.trim())
+                    .map_err(|err| err.with_query(line))?;
+                total
+                    .checked_add(Credits(price))
+                    .ok_or(QueryError::PriceOverflow)
+            })
+    }
+
+    fn line_cost(&self, line: &str) -> Result<Decimal, QueryError> {
+        let captures = LIST_ENTRY
+            .captures(line)
+            .ok_or_else(|| QueryError::MalformedLine(line.to_string()))?;
+        let (count, _) = self.quantity(captures.get(1).unwrap().as_str())?;
+        let item = item_name(captures.get(2).unwrap().as_str());
+
+        self.total_price(&item, count)
+    }
+
+    /// Renames an item, keeping its price.
+    pub fn rename_item<S>(&mut self, old: &str, new: S) -> Result<(), QueryError>
+    where
+        Cow<'a, str>: From<S>,
+    {
+        let new: Cow<'a, str> = From::from(new);
+
+        if self.price_set.contains_key(&new) {
+            return Err(QueryError::ItemAlreadyExists(new.into_owned()));
+        }
+
+        let (old, price) = self
+            .price_set
+            .remove_entry(old)
+            .ok_or_else(|| QueryError::UnrecognizedItem(old.to_string()))?;
+
+        self.price_set.insert(new.clone(), price);
+        self.history.push(Change::ItemRenamed(old, new));
+
+        Ok(())
+    }
+
+    /// Renames an intergalactic word, keeping its digit.
+    ///
+    /// Prices defined with the old word are kept.
+    pub fn rename_word<S>(&mut self, old: &str, new: S) -> Result<(), QueryError>
+    where
+        Cow<'a, str>: From<S>,
+    {
+        let new: Cow<'a, str> = From::from(new);
+
+        let digit = self
+            .language
+            .get(old)
+            .ok_or_else(|| QueryError::UnrecognizedWord(old.to_string()))?;
+
+        if self.language.contains(&new) {
+            return Err(QueryError::WordAlreadyExists(new.into_owned()));
+        }
+
+        self.language.remove(old);
+        self.language.insert::<Cow<'a, str>>(new.clone(), digit);
+        self.history
+            .push(Change::WordRenamed(Cow::Owned(old.to_string()), new, digit));
+
+        Ok(())
+    }
+
+    /// Reverts the most recent word or item definition, price update, rename, forgotten word
+    /// or cleared prices.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(Change::Word(word, digit)) => {
+                self.language.remove(&word);
+                self.known_digits.remove(&digit);
+                true
+            }
+            Some(Change::WordForgotten(word, digit)) => {
+                self.language.insert(word, digit);
+                self.known_digits.insert(digit);
+                true
+            }
+            Some(Change::Item(item)) => {
+                self.price_set.remove(&item);
+                true
+            }
+            Some(Change::PricesCleared(prices)) => {
+                self.price_set = prices;
+                true
+            }
+            Some(Change::PriceUpdated(item, price)) => {
+                self.price_set.insert(item, price);
+                true
+            }
+            Some(Change::ItemRenamed(old, new)) => {
+                if let Some(price) = self.price_set.remove(&new) {
+                    self.price_set.insert(old, price);
+                }
+                true
+            }
+            Some(Change::WordRenamed(old, new, digit)) => {
+                self.language.remove(&new);
+                self.language.insert(old, digit);
+                true
+            }
+            None => false,
         }
     }
 
@@ -46,18 +1060,37 @@ impl<'a> Ford<'a> {
     ///
     /// Valid queries are of one of the following forms:
     /// - <number> is <roman digit>
+    /// - Forget <word>, or <word> is unknown
+    /// - Forget all prices
     /// - <number> <Item> is <decimal> credits
     /// - How much is <number>?
     /// - How many credits is <number> <Item>?
+    /// - What is the price of <number> <Item>? (or What's the price of ...)
+    /// - How many credits is <number> <Item> in intergalactic?
+    /// - How many credits is <number> <Item>, <number> <Item> and <number> <Item>?
+    /// - How many <currency> is <number> <Item>?
+    /// - How many credits is <Item> per unit?
+    /// - How many <Item> for <decimal> credits?
+    /// - How many <Item> equals <number> <Item>?
+    /// - How much is <number> <Item> in <Item>?
+    /// - Rename <Item> to <Item>
+    /// - Rename <word> to <word>
+    /// - What is <word>?
+    /// - What is <decimal> in intergalactic?
+    /// - What items do you know?
+    /// - What is the average price?
     ///
     /// Numbers must always be all lowercase, while items must always be capitalized.
+    /// Items may span multiple words, everything from the first capitalized word on is the item.
     /// Roman digit can be one off: I, V, X, L, C, D, M.
+    /// Credit totals may be negative, e.g. for discounts.
+    /// Numbers in questions may also be decimal integers, e.g. `How much is 42?`.
     ///
     /// # Examples
     /// ```
     /// # use std::borrow::Cow;
     /// # use hashbrown::HashMap;
-    /// # use rust_decimal::Decimal;
+    /// # use rust_decimal::{Decimal, RoundingStrategy};
     /// # use rust_decimal_macros::dec;
     /// # use intra::language::Language;
     /// # use intra::Ford;
@@ -79,7 +1112,7 @@ impl<'a> Ford<'a> {
     /// ```
     /// # use std::borrow::Cow;
     /// # use hashbrown::HashMap;
-    /// # use rust_decimal::Decimal;
+    /// # use rust_decimal::{Decimal, RoundingStrategy};
     /// # use rust_decimal_macros::dec;
     /// # use intra::language::Language;
     /// # use intra::Ford;
@@ -98,67 +1131,361 @@ impl<'a> Ford<'a> {
     /// // Gold costs 10 credits per unit.
     /// ford.query("How many credits is glob glob Gold?").unwrap();
     /// ```
+    ///
+    /// Errors are wrapped with the query that caused them, see `QueryError::with_query`.
     pub fn query(&mut self, query: &str) -> Result<Option<String>, QueryError> {
-        if let Some(captures) = QUERY_SET_DIGIT.captures(query) {
-            let intergalactic = captures.get(1).unwrap().as_str();
-            let roman = captures.get(2).unwrap().as_str().chars().next().unwrap();
-
-            if self.language.contains(intergalactic) {
-                return Err(QueryError::WordAlreadyExists(intergalactic.to_string()));
-            }
+        self.answer(query).map_err(|err| err.with_query(query))
+    }
 
-            if self.known_digits.contains(&roman) {
-                return Err(QueryError::DigitAlreadyExists(roman));
+    /// Classifies a query by its form, without answering it.
+    ///
+    /// Returns `None` if the query isn't of any known form.
+    pub fn classify(query: &str) -> Option<QueryKind> {
+        if QUERY_SET_DIGIT.is_match(query) {
+            Some(QueryKind::SetDigit)
+        } else if QUERY_FORGET_WORD.is_match(query) {
+            Some(QueryKind::ForgetWord)
+        } else if QUERY_CLEAR_PRICES.is_match(query) {
+            Some(QueryKind::ClearPrices)
+        } else if QUERY_SET_ITEM.is_match(query) {
+            Some(QueryKind::SetItem)
+        } else if QUERY_NUMERAL.is_match(query) {
+            Some(QueryKind::AskNumber)
+        } else if QUERY_PRICE_INTERGALACTIC.is_match(query) {
+            Some(QueryKind::AskPriceIntergalactic)
+        } else if QUERY_PRICE_LIST
+            .captures(query)
+            .and_then(|captures| price_list(captures.get(1).unwrap().as_str()))
+            .is_some()
+        {
+            Some(QueryKind::AskTotalPrice)
+        } else if QUERY_UNIT_PRICE.is_match(query) {
+            Some(QueryKind::AskUnitPrice)
+        } else if QUERY_PRICE.is_match(query) {
+            Some(QueryKind::AskPrice)
+        } else if QUERY_PRICE_IN.is_match(query) {
+            Some(QueryKind::AskPriceIn)
+        } else if QUERY_RENAME_ITEM.is_match(query) {
+            Some(QueryKind::RenameItem)
+        } else if QUERY_RENAME_WORD.is_match(query) {
+            Some(QueryKind::RenameWord)
+        } else if QUERY_BUDGET.is_match(query) {
+            Some(QueryKind::AskBudget)
+        } else if QUERY_EXCHANGE.is_match(query) {
+            Some(QueryKind::AskExchange)
+        } else if QUERY_PRICE_IN_ITEM.is_match(query) {
+            Some(QueryKind::AskPriceInItem)
+        } else if QUERY_WORD.is_match(query) {
+            Some(QueryKind::AskWord)
+        } else if QUERY_INTERGALACTIC.is_match(query) {
+            Some(QueryKind::AskIntergalactic)
+        } else if QUERY_ITEMS.is_match(query) {
+            Some(QueryKind::ListItems)
+        } else if QUERY_AVERAGE_PRICE.is_match(query) {
+            Some(QueryKind::AskAveragePrice)
+        } else {
+            None
+        }
+    }
+
+    /// Finds an item that isn't capitalized in a query that isn't recognized otherwise,
+    /// such as `gold` in `How many credits is glob gold?`.
+    ///
+    /// The item starts at the first unknown word, which must follow at least one known word.
+    fn lowercase_item(&self, query: &str) -> Option<String> {
+        let captures = QUERY_LOWERCASE_PRICE
+            .captures(query)
+            .or_else(|| QUERY_LOWERCASE_SET_ITEM.captures(query))?;
+        let mut words = captures
+            .get(1)
+            .unwrap()
+            .as_str()
+            .split_whitespace()
+            .peekable();
+
+        let mut amount = 0;
+        while words.next_if(|word| self.language.contains(word)).is_some() {
+            amount += 1;
+        }
+
+        let item = words.collect::<Vec<_>>().join(" ");
+        (amount > 0 && !item.is_empty()).then_some(item)
+    }
+
+    fn answer(&mut self, query: &str) -> Result<Option<String>, QueryError> {
+        let kind = Self::classify(query).ok_or_else(|| match self.lowercase_item(query) {
+            Some(item) => QueryError::LowercaseItem(item),
+            None => QueryError::UnrecognizedQuery(query.to_string()),
+        })?;
+
+        match kind {
+            QueryKind::SetDigit => {
+                let captures = QUERY_SET_DIGIT.captures(query).unwrap();
+                let intergalactic = captures.get(1).unwrap().as_str();
+                let roman = captures.get(2).unwrap().as_str().chars().next().unwrap();
+
+                self.define_word(intergalactic.to_string(), roman)?;
+
+                Ok(self.acknowledge(format_args!("{intergalactic} = {roman}")))
+            }
+            QueryKind::ForgetWord => {
+                let captures = QUERY_FORGET_WORD.captures(query).unwrap();
+                let word = captures
+                    .get(1)
+                    .or_else(|| captures.get(2))
+                    .unwrap()
+                    .as_str();
+
+                self.forget_word(word)?;
+
+                Ok(self.acknowledge(format_args!("forgot {word}")))
             }
+            QueryKind::ClearPrices => {
+                self.clear_prices();
 
-            self.language.insert(intergalactic.to_string(), roman);
-            self.known_digits.insert(roman);
+                Ok(self.acknowledge(format_args!("forgot all prices")))
+            }
+            QueryKind::SetItem => {
+                let captures = QUERY_SET_ITEM.captures(query).unwrap();
+                let intergalactic = captures.get(1).unwrap().as_str().trim();
+                let item = item_name(captures.get(2).unwrap().as_str());
+                let price = parse_price(captures.get(3).unwrap().as_str())?;
 
-            Ok(None)
-        } else if let Some(captures) = QUERY_SET_ITEM.captures(query) {
-            let intergalactic = captures.get(1).unwrap().as_str().trim();
-            let roman = self.language.translate(intergalactic)?;
-            let count = Decimal::from(u32::from(roman));
+                let ack = self.acknowledge(format_args!(
+                    "{intergalactic} {item} = {}",
+                    self.format_credits(price)
+                ));
+                self.define_item(intergalactic, item, price)?;
 
-            let item = captures.get(2).unwrap().as_str().trim();
+                Ok(ack)
+            }
+            QueryKind::AskNumber => {
+                let captures = QUERY_NUMERAL.captures(query).unwrap();
+                let intergalactic = captures.get(1).unwrap().as_str().trim();
+                let (decimal, roman) = self.quantity(intergalactic)?;
 
-            if self.price_set.contains_key(item) {
-                return Err(QueryError::ItemAlreadyExists(item.to_string()));
+                match roman {
+                    Some(roman) if self.show_roman => {
+                        Ok(Some(format!("{intergalactic} is {decimal} ({roman})")))
+                    }
+                    _ => Ok(Some(format!("{intergalactic} is {decimal}"))),
+                }
             }
+            QueryKind::AskPrice => {
+                let captures = QUERY_PRICE.captures(query).unwrap();
+                let intergalactic = captures.get(1).unwrap().as_str().trim();
+                let (count, _) = self.quantity(intergalactic)?;
+
+                let item = item_name(captures.get(2).unwrap().as_str());
+                let total_price = self.format_credits(self.total_price(&item, count)?);
 
-            let price = Decimal::from_str_exact(captures.get(3).unwrap().as_str()).unwrap();
-            let item_price = price / count;
+                if self.show_unit_price {
+                    let price = self.format_credits(self.unit_price(&item)?);
+                    Ok(Some(format!(
+                        "{intergalactic} {item} is {total_price} ({price} each)"
+                    )))
+                } else {
+                    Ok(Some(format!("{intergalactic} {item} is {total_price}")))
+                }
+            }
+            QueryKind::AskPriceIntergalactic => {
+                let captures = QUERY_PRICE_INTERGALACTIC.captures(query).unwrap();
+                let intergalactic = captures.get(1).unwrap().as_str().trim();
+                let (count, _) = self.quantity(intergalactic)?;
 
-            self.price_set
-                .insert(Cow::from(item.to_string()), item_price);
+                let item = item_name(captures.get(2).unwrap().as_str());
+                let words = self.format_as_intergalactic(self.total_price(&item, count)?)?;
 
-            Ok(None)
-        } else if let Some(captures) = QUERY_NUMERAL.captures(query) {
-            let intergalactic = captures.get(1).unwrap().as_str().trim();
-            let roman = self.language.translate(intergalactic)?;
+                let PriceFormat { prefix, suffix, .. } = &self.price_format;
+                Ok(Some(format!(
+                    "{intergalactic} {item} is {prefix}{words}{suffix}"
+                )))
+            }
+            QueryKind::AskTotalPrice => {
+                let captures = QUERY_PRICE_LIST.captures(query).unwrap();
+                let list = price_list(captures.get(1).unwrap().as_str()).unwrap();
 
-            let decimal = u32::from(roman);
+                // repeated items are priced once, for their combined quantity
+                let mut counts = Vec::<(&str, Quantity)>::new();
+                for (intergalactic, item) in &list {
+                    let (count, _) = self.quantity(intergalactic)?;
 
-            Ok(Some(format!("{intergalactic} is {decimal}")))
-        } else if let Some(captures) = QUERY_PRICE.captures(query) {
-            let intergalactic = captures.get(1).unwrap().as_str().trim();
-            let roman = self.language.translate(intergalactic)?;
-            let count = Decimal::from(u32::from(roman));
+                    match counts.iter_mut().find(|(known, _)| known == item) {
+                        Some((_, total)) => {
+                            *total = total.checked_add(count).ok_or(QueryError::PriceOverflow)?
+                        }
+                        None => counts.push((item, count)),
+                    }
+                }
 
-            let item = captures.get(2).unwrap().as_str().trim();
-            let price = self
-                .price_set
-                .get(&Cow::from(item))
-                .ok_or_else(|| QueryError::UnrecognizedItem(item.to_string()))?;
+                let mut total_price = Decimal::ZERO;
+                let mut unknown_items = HashSet::new();
 
-            let total_price = count * price;
-            let total_price = total_price.normalize();
+                for (item, count) in counts {
+                    let price = match self.total_price(item, count) {
+                        Ok(price) => price,
+                        Err(QueryError::UnrecognizedItem(_)) if self.skip_unknown_items => {
+                            unknown_items.insert(item);
+                            continue;
+                        }
+                        Err(err) => return Err(err),
+                    };
 
-            Ok(Some(format!(
-                "{intergalactic} {item} is {total_price} Credits"
-            )))
-        } else {
-            Err(QueryError::UnrecognizedQuery(query.to_string()))
+                    total_price = total_price
+                        .checked_add(price)
+                        .ok_or(QueryError::PriceOverflow)?;
+                }
+
+                let (unknown, priced): (Vec<_>, Vec<_>) = list
+                    .iter()
+                    .partition(|(_, item)| unknown_items.contains(item.as_str()));
+                let priced = priced
+                    .into_iter()
+                    .map(|(intergalactic, item)| format!("{intergalactic} {item}"))
+                    .collect::<Vec<_>>();
+
+                if priced.is_empty() {
+                    let (_, item) = unknown[0];
+                    return Err(QueryError::UnrecognizedItem(item.clone()));
+                }
+
+                let total_price = self.format_credits(total_price);
+                let answer = format!("{} is {total_price}", join_list(&priced));
+                if unknown.is_empty() {
+                    Ok(Some(answer))
+                } else {
+                    let unknown = unknown
+                        .into_iter()
+                        .map(|(intergalactic, item)| format!("{intergalactic} {item}"))
+                        .collect::<Vec<_>>();
+                    Ok(Some(format!("{answer} (unknown: {})", join_list(&unknown))))
+                }
+            }
+            QueryKind::AskPriceIn => {
+                let captures = QUERY_PRICE_IN.captures(query).unwrap();
+                let currency = captures.get(1).unwrap().as_str();
+                let intergalactic = captures.get(2).unwrap().as_str().trim();
+                let (count, _) = self.quantity(intergalactic)?;
+
+                let item = item_name(captures.get(3).unwrap().as_str());
+                let total_price = self.total_price(&item, count)?;
+                let total_price = self.format_currency(total_price, currency)?;
+
+                Ok(Some(format!("{intergalactic} {item} is {total_price}")))
+            }
+            QueryKind::AskUnitPrice => {
+                let captures = QUERY_UNIT_PRICE.captures(query).unwrap();
+                let item = item_name(captures.get(1).unwrap().as_str());
+                let price = self.format_credits(self.unit_price(&item)?);
+
+                Ok(Some(format!("{item} is {price} per unit")))
+            }
+            QueryKind::RenameItem => {
+                let captures = QUERY_RENAME_ITEM.captures(query).unwrap();
+                let old = item_name(captures.get(1).unwrap().as_str());
+                let new = item_name(captures.get(2).unwrap().as_str());
+
+                let ack = self.acknowledge(format_args!("{old} -> {new}"));
+                self.rename_item(&old, new)?;
+
+                Ok(ack)
+            }
+            QueryKind::RenameWord => {
+                let captures = QUERY_RENAME_WORD.captures(query).unwrap();
+                let old = captures.get(1).unwrap().as_str();
+                let new = captures.get(2).unwrap().as_str();
+
+                self.rename_word(old, new.to_string())?;
+
+                Ok(self.acknowledge(format_args!("{old} -> {new}")))
+            }
+            QueryKind::AskBudget => {
+                let captures = QUERY_BUDGET.captures(query).unwrap();
+                let item = item_name(captures.get(1).unwrap().as_str());
+                let budget = parse_price(captures.get(2).unwrap().as_str())?;
+
+                let count = self.affordable(&item, budget)?;
+                let budget = self.format_credits(budget);
+
+                match Roman::try_from(count) {
+                    Ok(roman) if self.show_roman => {
+                        Ok(Some(format!("{budget} buys {count} ({roman}) {item}")))
+                    }
+                    _ => Ok(Some(format!("{budget} buys {count} {item}"))),
+                }
+            }
+            QueryKind::AskExchange => {
+                let captures = QUERY_EXCHANGE.captures(query).unwrap();
+                let unit = item_name(captures.get(1).unwrap().as_str());
+                let intergalactic = captures.get(2).unwrap().as_str().trim();
+                let (count, _) = self.quantity(intergalactic)?;
+                let item = item_name(captures.get(3).unwrap().as_str());
+
+                let (units, left) = self.exchange(count, &item, &unit)?;
+                let units = self.format_exchange(units, &unit, left);
+
+                Ok(Some(format!("{intergalactic} {item} equals {units}")))
+            }
+            QueryKind::AskPriceInItem => {
+                let captures = QUERY_PRICE_IN_ITEM.captures(query).unwrap();
+                let intergalactic = captures.get(1).unwrap().as_str().trim();
+                let (count, _) = self.quantity(intergalactic)?;
+                let item = item_name(captures.get(2).unwrap().as_str());
+                let unit = item_name(captures.get(3).unwrap().as_str());
+
+                let (units, left) = self.exchange(count, &item, &unit)?;
+                let units = self.format_exchange(units, &unit, left);
+
+                Ok(Some(format!("{intergalactic} {item} is {units}")))
+            }
+            QueryKind::AskWord => {
+                let captures = QUERY_WORD.captures(query).unwrap();
+                let word = captures.get(1).unwrap().as_str();
+                let digit = self
+                    .language
+                    .get(word)
+                    .ok_or_else(|| QueryError::UnrecognizedWord(word.to_string()))?;
+                let value = Roman::digit_value(digit).ok_or(InvalidRomanNumeral)?;
+
+                Ok(Some(format!("{word} is {digit} ({value})")))
+            }
+            QueryKind::AskIntergalactic => {
+                let captures = QUERY_INTERGALACTIC.captures(query).unwrap();
+                let amount = captures.get(1).unwrap().as_str();
+                let value = amount
+                    .parse::<u32>()
+                    .map_err(|_| QueryError::InvalidQuantity(amount.to_string()))?;
+                let roman = Roman::try_from(value)?;
+                let intergalactic = self.language.to_intergalactic(&roman)?;
+
+                Ok(Some(format!("{value} is {intergalactic}")))
+            }
+            QueryKind::ListItems => {
+                let mut items = self.items().collect::<Vec<_>>();
+                if items.is_empty() {
+                    return Ok(Some("No items known yet".to_string()));
+                }
+
+                items.sort_unstable_by_key(|&(item, _)| item);
+                let items = items
+                    .into_iter()
+                    .map(|(item, price)| format!("{item} is {}", self.format_credits(price)))
+                    .collect::<Vec<_>>();
+
+                Ok(Some(items.join(", ")))
+            }
+            QueryKind::AskAveragePrice => {
+                let average = self.average_price().ok_or(if self.price_set.is_empty() {
+                    QueryError::NoPricesDefined
+                } else {
+                    QueryError::PriceOverflow
+                })?;
+
+                Ok(Some(format!(
+                    "The average price is {}",
+                    self.format_credits(average)
+                )))
+            }
         }
     }
 }
@@ -195,9 +1522,21 @@ mod tests {
             ford.query("How many credits is glob glob Gold?").unwrap(),
             Some("glob glob Gold is 20 Credits".to_string())
         );
+        assert_eq!(
+            ford.query("What is the price of glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 20 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("what's the price of pish Iron?").unwrap(),
+            Some("pish Iron is 10 Credits".to_string())
+        );
 
         // negative tests
         assert!(ford.query("How much is foo bar?").is_err());
+        assert!(ford.query("What is the price of glob glob gold?").is_err());
+        assert!(ford
+            .query("What is the price of glob glob Copper?")
+            .is_err());
         assert!(ford.query("What is pish tegj glob glob?").is_err());
         assert!(ford.query("How many credits is glob glob Copper?").is_err());
         assert!(ford
@@ -206,4 +1545,1439 @@ mod tests {
         assert!(ford.query("glob is I").is_err());
         assert!(ford.query("glob Gold is 5 Credits").is_err());
     }
+
+    #[test]
+    fn define() {
+        let mut ford = Ford::new();
+
+        // positive tests
+        ford.define_word("glob", 'I').unwrap();
+        ford.define_word("prok", 'V').unwrap();
+        ford.define_item("glob prok", "Gold", dec!(57800)).unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 28900 Credits".to_string())
+        );
+
+        // negative tests
+        assert!(ford.define_word("glob", 'X').is_err());
+        assert!(ford.define_word("pish", 'I').is_err());
+        assert!(ford.define_word("pish", 'Q').is_err());
+        assert!(ford.define_item("glob", "Gold", dec!(10)).is_err());
+        assert!(ford.define_item("foo", "Silver", dec!(10)).is_err());
+    }
+
+    #[test]
+    fn invalid_price() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+
+        // too many digits to fit in a `Decimal`
+        let err = ford
+            .query("glob Silver is 123456789012345678901234567890123 Credits")
+            .unwrap_err();
+        assert!(matches!(
+            err.cause(),
+            QueryError::InvalidPrice(price) if price == "123456789012345678901234567890123"
+        ));
+        let err = ford
+            .query("How many Gold for 123456789012345678901234567890123 credits?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InvalidPrice(_)));
+    }
+
+    #[test]
+    fn lowercase_item() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+
+        let err = ford.query("how many credits is glob gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::LowercaseItem(item) if item == "gold"));
+        assert_eq!(
+            err.to_string(),
+            "Unrecognized item: `gold`, item names must be capitalized \
+             in query `how many credits is glob gold?`"
+        );
+        let err = ford
+            .query("What is the price of glob glob soy milk?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::LowercaseItem(item) if item == "soy milk"));
+        let err = ford.query("glob silver is 5 credits").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::LowercaseItem(item) if item == "silver"));
+
+        // not an item
+        let err = ford.query("how many credits is gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedQuery(_)));
+        let err = ford.query("how many credits is glob glob?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedQuery(_)));
+        let err = ford.query("Who is glob?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedQuery(_)));
+    }
+
+    #[test]
+    fn error_context() {
+        let mut ford = Ford::new();
+        ford.define_word("glob", 'I').unwrap();
+
+        let err = ford.query("How much is glob foo?").unwrap_err();
+        assert_eq!(err.query(), Some("How much is glob foo?"));
+        assert!(matches!(err.cause(), QueryError::UnrecognizedWord(word) if word == "foo"));
+
+        let err = ford.query("Who is glob?").unwrap_err();
+        assert_eq!(err.query(), Some("Who is glob?"));
+        assert!(matches!(err.cause(), QueryError::UnrecognizedQuery(_)));
+    }
+
+    #[test]
+    fn show_roman() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+
+        assert_eq!(
+            ford.query("How much is glob glob?").unwrap(),
+            Some("glob glob is 2".to_string())
+        );
+
+        ford.set_show_roman(true);
+        assert_eq!(
+            ford.query("How much is glob glob?").unwrap(),
+            Some("glob glob is 2 (II)".to_string())
+        );
+
+        ford.set_show_roman(false);
+        assert_eq!(
+            ford.query("How much is glob glob?").unwrap(),
+            Some("glob glob is 2".to_string())
+        );
+    }
+
+    #[test]
+    fn no_prices() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+
+        let err = ford.query("How many credits is glob Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::NoPricesDefined));
+
+        ford.query("glob Silver is 17 Credits").unwrap();
+        let err = ford.query("How many credits is glob Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(_)));
+    }
+
+    #[test]
+    fn rounding() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob glob Silver is 10 Credits").unwrap();
+
+        assert_eq!(
+            ford.query("How many credits is glob Silver?").unwrap(),
+            Some("glob Silver is 3.3333333333333333333333333333 Credits".to_string())
+        );
+
+        ford.set_rounding(Rounding::DecimalPlaces(
+            2,
+            RoundingStrategy::MidpointNearestEven,
+        ));
+        assert_eq!(
+            ford.query("How many credits is glob Silver?").unwrap(),
+            Some("glob Silver is 3.33 Credits".to_string())
+        );
+
+        ford.set_rounding(Rounding::DecimalPlaces(2, RoundingStrategy::AwayFromZero));
+        assert_eq!(
+            ford.query("How many credits is glob Silver?").unwrap(),
+            Some("glob Silver is 3.34 Credits".to_string())
+        );
+    }
+
+    #[test]
+    fn discount() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob Coupon is -5 Credits").unwrap();
+
+        assert_eq!(
+            ford.query("How many credits is glob Coupon?").unwrap(),
+            Some("glob Coupon is -2.5 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Coupon?")
+                .unwrap(),
+            Some("glob glob glob Coupon is -7.5 Credits".to_string())
+        );
+    }
+
+    #[test]
+    fn item_names() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+
+        // single word
+        ford.query("glob glob Gold is 20 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob Gold?").unwrap(),
+            Some("glob Gold is 10 Credits".to_string())
+        );
+
+        // multiple capitalized words
+        ford.query("glob glob White Gold is 30 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob White  Gold ?")
+                .unwrap(),
+            Some("glob White Gold is 15 Credits".to_string())
+        );
+
+        // only the first word must be capitalized
+        ford.query("glob Soy milk is 3 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob Soy milk?")
+                .unwrap(),
+            Some("glob glob Soy milk is 6 Credits".to_string())
+        );
+
+        // numeral words after the first capitalized word are part of the item
+        assert!(ford.query("How many credits is glob Gold glob?").is_err());
+        ford.query("glob Gold glob is 1 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob Gold glob?").unwrap(),
+            Some("glob Gold glob is 1 Credits".to_string())
+        );
+    }
+
+    #[test]
+    fn exact_totals() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob glob Item is 10 Credits").unwrap();
+
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Item?")
+                .unwrap(),
+            Some("glob glob glob Item is 10 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is 6 Item?").unwrap(),
+            Some("6 Item is 20 Credits".to_string())
+        );
+        assert_eq!(ford.affordable("Item", dec!(10)).unwrap(), 3);
+        assert_eq!(
+            ford.budget_breakdown("Item", dec!(20)).unwrap(),
+            (Quantity(6), Credits::ZERO)
+        );
+        assert!(ford
+            .to_script()
+            .contains("glob glob glob Item is 10 Credits"));
+    }
+
+    #[test]
+    fn restated_price() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+
+        // consistent
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob glob Gold is 20 Credits").unwrap();
+        ford.query("prok Gold is 50 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Gold?")
+                .unwrap(),
+            Some("glob glob glob Gold is 30 Credits".to_string())
+        );
+
+        // restatements don't change anything, so there's nothing to undo
+        assert!(ford.undo());
+        assert!(ford.query("How many credits is glob Gold?").is_err());
+
+        // contradictory
+        ford.query("glob Silver is 10 Credits").unwrap();
+        let err = ford.query("glob glob Silver is 30 Credits").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InconsistentPrice(_)));
+        assert_eq!(
+            ford.query("How many credits is glob glob Silver?").unwrap(),
+            Some("glob glob Silver is 20 Credits".to_string())
+        );
+    }
+
+    #[test]
+    fn price_tolerance() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob glob Silver is 10 Credits").unwrap();
+
+        ford.query("glob glob glob Silver is 10 Credits").unwrap();
+        let err = ford.query("glob Silver is 3 Credits").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InconsistentPrice(_)));
+
+        // within tolerance
+        ford.set_price_tolerance(dec!(0.5));
+        ford.query("glob Silver is 3 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Silver?")
+                .unwrap(),
+            Some("glob glob glob Silver is 10 Credits".to_string())
+        );
+
+        // outside tolerance
+        ford.set_price_tolerance(dec!(0.1));
+        let err = ford.query("glob Silver is 3 Credits").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InconsistentPrice(_)));
+
+        // the difference overflows
+        ford.query("glob Iron is -79228162514264337593543950335 Credits")
+            .unwrap();
+        let err = ford
+            .query("glob Iron is 79228162514264337593543950335 Credits")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InconsistentPrice(_)));
+    }
+
+    #[test]
+    fn price_updates() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+
+        // strict by default
+        let err = ford.query("glob Gold is 12 Credits").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InconsistentPrice(_)));
+        assert_eq!(
+            ford.query("How many credits is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 20 Credits".to_string())
+        );
+
+        // overwrite
+        ford.set_allow_price_updates(true);
+        ford.query("glob Gold is 12 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 24 Credits".to_string())
+        );
+        ford.query("glob glob glob Silver is 10 Credits").unwrap();
+        ford.query("glob Silver is 4 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Silver?")
+                .unwrap(),
+            Some("glob glob glob Silver is 12 Credits".to_string())
+        );
+        ford.query("glob glob Silver is 8 Credits").unwrap();
+
+        // updates can be undone, restating the same price isn't an update
+        assert!(ford.undo());
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Silver?")
+                .unwrap(),
+            Some("glob glob glob Silver is 10 Credits".to_string())
+        );
+
+        // strict again
+        ford.set_allow_price_updates(false);
+        let err = ford.query("glob Gold is 10 Credits").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InconsistentPrice(_)));
+    }
+
+    #[test]
+    fn budget() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob Coupon is -10 Credits").unwrap();
+
+        // exact budget
+        assert_eq!(ford.affordable("Gold", dec!(100)).unwrap(), 10);
+        assert_eq!(
+            ford.query("How many Gold for 100 credits?").unwrap(),
+            Some("100 Credits buys 10 Gold".to_string())
+        );
+
+        // inexact budget
+        assert_eq!(ford.affordable("Gold", dec!(109)).unwrap(), 10);
+        assert_eq!(ford.affordable("Gold", dec!(9)).unwrap(), 0);
+        ford.set_show_roman(true);
+        assert_eq!(
+            ford.query("How many Gold for 109 Credits ?").unwrap(),
+            Some("109 Credits buys 10 (X) Gold".to_string())
+        );
+
+        // fractional budget
+        assert_eq!(
+            ford.query("How many Gold for 25.5 credits?").unwrap(),
+            Some("25.5 Credits buys 2 (II) Gold".to_string())
+        );
+
+        // negative tests
+        assert!(ford.query("How many Silver for 100 credits?").is_err());
+        assert!(ford
+            .query("xyz How many Gold for 25 credits? nonsense")
+            .is_err());
+        let err = ford.query("How many Coupon for 100 credits?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::NonPositivePrice(_)));
+        assert!(matches!(
+            ford.affordable("Gold", dec!(-5)),
+            Err(QueryError::InvalidPrice(_))
+        ));
+        assert!(matches!(
+            ford.budget_breakdown("Gold", dec!(-5)),
+            Err(QueryError::InvalidPrice(_))
+        ));
+    }
+
+    #[test]
+    fn total_price() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob Silver is 2 Credits").unwrap();
+        ford.query("glob Soy milk is 3 Credits").unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.query("How many credits is glob Gold and prok Silver?")
+                .unwrap(),
+            Some("glob Gold and prok Silver is 20 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits are glob Gold, glob glob Soy milk, and 3 Silver?")
+                .unwrap(),
+            Some("glob Gold, glob glob Soy milk and 3 Silver is 22 Credits".to_string())
+        );
+        // repeated items are summed
+        assert_eq!(
+            ford.query("How many credits is glob Gold and glob Gold?")
+                .unwrap(),
+            Some("glob Gold and glob Gold is 20 Credits".to_string())
+        );
+        ford.query("glob glob glob Tea is 10 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob Tea, glob Gold and glob glob Tea?")
+                .unwrap(),
+            Some("glob Tea, glob Gold and glob glob Tea is 20 Credits".to_string())
+        );
+        // a single item is still a price question
+        assert_eq!(
+            ford.query("How many credits is glob Soy milk?").unwrap(),
+            Some("glob Soy milk is 3 Credits".to_string())
+        );
+
+        // negative tests
+        let err = ford
+            .query("How many credits is glob Gold and glob Copper?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(_)));
+        let err = ford
+            .query("How many credits is glob Gold and zorg Silver?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedWord(_)));
+    }
+
+    #[test]
+    fn total_cost() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob glob Silver is 3 Credits").unwrap();
+
+        // positive tests
+        let list = "glob Gold\n\nprok Silver\n  3 Gold  \n";
+        assert_eq!(ford.total_cost(list.lines()).unwrap(), dec!(47.5));
+        assert_eq!(ford.total_cost([]).unwrap(), Decimal::ZERO);
+
+        // negative tests
+        let err = ford
+            .total_cost(["glob Gold", "glob Copper", "glob Silver"])
+            .unwrap_err();
+        assert_eq!(err.query(), Some("glob Copper"));
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(item) if item == "Copper"));
+        let err = ford.total_cost(["Gold"]).unwrap_err();
+        assert!(matches!(err.cause(), QueryError::MalformedLine(_)));
+    }
+
+    #[test]
+    fn skip_unknown_items() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob Silver is 2 Credits").unwrap();
+        ford.set_skip_unknown_items(true);
+
+        // positive tests
+        assert_eq!(
+            ford.query("How many credits is glob Gold, glob Copper and glob glob Silver?")
+                .unwrap(),
+            Some("glob Gold and glob glob Silver is 14 Credits (unknown: glob Copper)".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is glob Tin and glob Gold and glob Lead?")
+                .unwrap(),
+            Some("glob Gold is 10 Credits (unknown: glob Tin and glob Lead)".to_string())
+        );
+
+        // negative tests
+        let err = ford
+            .query("How many credits is glob Tin and glob Lead?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(item) if item == "Tin"));
+        // unknown words still fail the question
+        assert!(ford
+            .query("How many credits is glob Gold and zorg Copper?")
+            .is_err());
+    }
+
+    #[test]
+    fn price_intergalactic() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("pish is X").unwrap();
+        ford.query("glob glob Gold is 24 Credits").unwrap();
+        ford.query("glob glob Silver is 3 Credits").unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.query("How many credits is glob Gold in intergalactic?")
+                .unwrap(),
+            Some("glob Gold is pish glob glob Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is glob glob Silver in Intergalactic ?")
+                .unwrap(),
+            Some("glob glob Silver is glob glob glob Credits".to_string())
+        );
+
+        // negative tests
+        let err = ford
+            .query("How many credits is glob Silver in intergalactic?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::FractionalPrice(price) if price == "1.5"));
+        let err = ford
+            .query("How many credits is 5000 Gold in intergalactic?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InvalidRomanNumeral(_)));
+        let err = ford
+            .query("How many credits is pish pish Gold in intergalactic?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::MissingDigitWord('C')));
+    }
+
+    #[test]
+    fn format_as_intergalactic() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("pish is X").unwrap();
+        ford.query("tegj is L").unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.format_as_intergalactic(dec!(42)).unwrap(),
+            "pish tegj glob glob"
+        );
+        assert_eq!(ford.format_as_intergalactic(dec!(1.00)).unwrap(), "glob");
+
+        // negative tests
+        assert!(matches!(
+            ford.format_as_intergalactic(dec!(4.5)),
+            Err(QueryError::FractionalPrice(price)) if price == "4.5"
+        ));
+        assert!(matches!(
+            ford.format_as_intergalactic(Decimal::ZERO),
+            Err(QueryError::InvalidRomanNumeral(_))
+        ));
+        assert!(matches!(
+            ford.format_as_intergalactic(dec!(-5)),
+            Err(QueryError::InvalidRomanNumeral(_))
+        ));
+        assert!(matches!(
+            ford.format_as_intergalactic(dec!(4000)),
+            Err(QueryError::InvalidRomanNumeral(_))
+        ));
+        assert!(matches!(
+            ford.format_as_intergalactic(dec!(100)),
+            Err(QueryError::MissingDigitWord('C'))
+        ));
+    }
+
+    #[test]
+    fn show_unit_price() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob Gold is 25 Credits").unwrap();
+
+        assert_eq!(
+            ford.query("How many credits is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 25 Credits".to_string())
+        );
+
+        ford.set_show_unit_price(true);
+        assert_eq!(
+            ford.query("How many credits is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 25 Credits (12.5 Credits each)".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Gold?")
+                .unwrap(),
+            Some("glob glob glob Gold is 37.5 Credits (12.5 Credits each)".to_string())
+        );
+
+        // the unit price is formatted like the total
+        ford.query("glob glob Platinum is 5000 Credits").unwrap();
+        ford.set_price_format(PriceFormat {
+            grouping: Some(','),
+            prefix: "₡".to_string(),
+            suffix: String::new(),
+        });
+        assert_eq!(
+            ford.query("How many credits is glob glob glob Platinum?")
+                .unwrap(),
+            Some("glob glob glob Platinum is ₡7,500 (₡2,500 each)".to_string())
+        );
+    }
+
+    #[test]
+    fn currencies() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob Gold is 20 Credits").unwrap();
+        ford.define_currency("Bottlecaps", dec!(2.5)).unwrap();
+        ford.define_currency("Zorkmids", dec!(0.1)).unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.query("How many bottlecaps is glob Gold?").unwrap(),
+            Some("glob Gold is 25 Bottlecaps".to_string())
+        );
+        assert_eq!(
+            ford.query("How many Zorkmids is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 2 Zorkmids".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is glob Gold?").unwrap(),
+            Some("glob Gold is 10 Credits".to_string())
+        );
+
+        // negative tests
+        let err = ford.query("How many dollars is glob Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedCurrency(_)));
+        assert!(matches!(
+            ford.define_currency("Nothing", Decimal::ZERO),
+            Err(QueryError::NonPositiveRate(_))
+        ));
+    }
+
+    #[test]
+    fn unit_price() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob White Gold is 25 Credits").unwrap();
+        ford.query("glob glob Iron is 7 Credits").unwrap();
+        ford.query("glob Copper is 2 Credits").unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.query("How many credits is Iron per unit?").unwrap(),
+            Some("Iron is 3.5 Credits per unit".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is Copper per unit?").unwrap(),
+            Some("Copper is 2 Credits per unit".to_string())
+        );
+        assert!(!ford.language().contains("credits"));
+        assert_eq!(
+            ford.query("How many credits is White Gold per unit?")
+                .unwrap(),
+            Some("White Gold is 12.5 Credits per unit".to_string())
+        );
+        assert_eq!(
+            ford.query("how many credits is White Gold per ?").unwrap(),
+            Some("White Gold is 12.5 Credits per unit".to_string())
+        );
+        assert_eq!(
+            ford.query("How many credits is glob glob White Gold?")
+                .unwrap(),
+            Some("glob glob White Gold is 25 Credits".to_string())
+        );
+
+        // negative tests
+        assert!(ford.query("How many credits is Silver per unit?").is_err());
+        assert!(ford
+            .query("How many credits is glob White Gold per unit?")
+            .is_err());
+    }
+
+    #[test]
+    fn budget_breakdown() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob glob Gold is 10 Credits").unwrap();
+        ford.query("glob Coupon is 0 Credits").unwrap();
+
+        // even budget
+        assert_eq!(
+            ford.budget_breakdown("Gold", dec!(100)).unwrap(),
+            (Quantity(30), Credits(dec!(0)))
+        );
+
+        // uneven budget
+        assert_eq!(
+            ford.budget_breakdown("Gold", dec!(101)).unwrap(),
+            (Quantity(30), Credits(dec!(1)))
+        );
+        assert_eq!(
+            ford.budget_breakdown("Gold", dec!(2.5)).unwrap(),
+            (Quantity(0), Credits(dec!(2.5)))
+        );
+
+        // negative tests
+        assert!(ford.budget_breakdown("Silver", dec!(100)).is_err());
+        assert!(matches!(
+            ford.budget_breakdown("Coupon", dec!(100)),
+            Err(QueryError::NonPositivePrice(_))
+        ));
+    }
+
+    #[test]
+    fn exchange() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob Silver is 4 Credits").unwrap();
+        ford.query("glob Dirt is 0 Credits").unwrap();
+
+        // even
+        assert_eq!(
+            ford.exchange(Quantity(2), "Gold", "Silver").unwrap(),
+            (Quantity(5), Credits(dec!(0)))
+        );
+        assert_eq!(
+            ford.query("How many Silver equals glob glob Gold?")
+                .unwrap(),
+            Some("glob glob Gold equals 5 Silver".to_string())
+        );
+
+        // uneven
+        assert_eq!(
+            ford.exchange(Quantity(1), "Gold", "Silver").unwrap(),
+            (Quantity(2), Credits(dec!(2)))
+        );
+        assert_eq!(
+            ford.query("how many Silver equal prok Gold?").unwrap(),
+            Some("prok Gold equals 12 Silver and 2 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("How many Gold equals glob Silver?").unwrap(),
+            Some("glob Silver equals 0 Gold and 4 Credits".to_string())
+        );
+
+        // negative tests
+        let err = ford.query("How many Copper equals glob Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(_)));
+        let err = ford
+            .query("How many Silver equals glob Copper?")
+            .unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(_)));
+        let err = ford.query("How many Dirt equals glob Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::NonPositivePrice(_)));
+    }
+
+    #[test]
+    fn price_in_item() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob Silver is 4 Credits").unwrap();
+        ford.query("glob Dirt is 0 Credits").unwrap();
+        ford.query("glob White Gold is 15 Credits").unwrap();
+
+        // even
+        assert_eq!(
+            ford.query("How much is glob glob Gold in Silver?").unwrap(),
+            Some("glob glob Gold is 5 Silver".to_string())
+        );
+        assert_eq!(
+            ford.query("how much is glob glob White Gold in Gold ?")
+                .unwrap(),
+            Some("glob glob White Gold is 3 Gold".to_string())
+        );
+
+        // uneven
+        assert_eq!(
+            ford.query("How much is prok Gold in Silver?").unwrap(),
+            Some("prok Gold is 12 Silver and 2 Credits".to_string())
+        );
+
+        // negative tests
+        let err = ford.query("How much is glob Gold in Copper?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(_)));
+        let err = ford.query("How much is glob Copper in Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedItem(_)));
+        let err = ford.query("How much is glob Gold in Dirt?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::NonPositivePrice(_)));
+    }
+
+    #[test]
+    fn rename_item() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Iron is 10 Credits").unwrap();
+        ford.query("glob Gold is 20 Credits").unwrap();
+
+        // positive tests
+        ford.rename_item("Iron", "Steel").unwrap();
+        assert!(ford.query("How many credits is glob Iron?").is_err());
+        assert_eq!(
+            ford.query("How many credits is glob glob Steel?").unwrap(),
+            Some("glob glob Steel is 20 Credits".to_string())
+        );
+
+        ford.query("rename Steel to Stainless Steel").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob Stainless Steel?")
+                .unwrap(),
+            Some("glob Stainless Steel is 10 Credits".to_string())
+        );
+
+        assert!(ford.undo());
+        assert!(ford.query("How many credits is glob Steel?").is_ok());
+
+        // negative tests
+        let err = ford.rename_item("Iron", "Copper").unwrap_err();
+        assert!(matches!(err, QueryError::UnrecognizedItem(_)));
+        let err = ford.rename_item("Steel", "Gold").unwrap_err();
+        assert!(matches!(err, QueryError::ItemAlreadyExists(_)));
+        let err = ford.query("rename Steel to Gold").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::ItemAlreadyExists(_)));
+        assert!(ford.query("How many credits is glob Steel?").is_ok());
+    }
+
+    #[test]
+    fn rename_word() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob glob Gold is 20 Credits").unwrap();
+
+        // positive tests
+        ford.rename_word("glob", "glub").unwrap();
+        assert!(ford.query("How much is glob?").is_err());
+        assert_eq!(
+            ford.query("How many credits is glub prok Gold?").unwrap(),
+            Some("glub prok Gold is 40 Credits".to_string())
+        );
+
+        ford.query("rename glub to glib").unwrap();
+        assert_eq!(
+            ford.query("How much is glib glib?").unwrap(),
+            Some("glib glib is 2".to_string())
+        );
+
+        assert!(ford.undo());
+        assert!(ford.query("How much is glub?").is_ok());
+        assert!(ford.query("How much is glib?").is_err());
+
+        // negative tests
+        let err = ford.rename_word("glob", "glab").unwrap_err();
+        assert!(matches!(err, QueryError::UnrecognizedWord(word) if word == "glob"));
+        let err = ford.rename_word("glub", "prok").unwrap_err();
+        assert!(matches!(err, QueryError::WordAlreadyExists(word) if word == "prok"));
+        let err = ford.query("rename glub to prok").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::WordAlreadyExists(_)));
+        assert_eq!(ford.language().get("glub"), Some('I'));
+        assert_eq!(ford.language().get("prok"), Some('V'));
+    }
+
+    #[test]
+    fn query_kind() {
+        let kinds = [
+            ("glob is I", QueryKind::SetDigit, "set-digit"),
+            ("glob Gold is 10 Credits", QueryKind::SetItem, "set-item"),
+            ("How much is glob?", QueryKind::AskNumber, "ask-number"),
+            (
+                "How many credits is glob Gold?",
+                QueryKind::AskPrice,
+                "ask-price",
+            ),
+            (
+                "How many credits is Gold per unit?",
+                QueryKind::AskUnitPrice,
+                "ask-unit-price",
+            ),
+            ("rename Gold to Iron", QueryKind::RenameItem, "rename-item"),
+            ("rename glob to glub", QueryKind::RenameWord, "rename-word"),
+            (
+                "How many Silver equals glob Gold?",
+                QueryKind::AskExchange,
+                "ask-exchange",
+            ),
+            (
+                "How much is glob Gold in Silver?",
+                QueryKind::AskPriceInItem,
+                "ask-price-in-item",
+            ),
+            (
+                "How many Gold for 10 credits?",
+                QueryKind::AskBudget,
+                "ask-budget",
+            ),
+            ("What is glob?", QueryKind::AskWord, "ask-word"),
+            (
+                "What items do you know?",
+                QueryKind::ListItems,
+                "list-items",
+            ),
+            (
+                "What is 42 in intergalactic?",
+                QueryKind::AskIntergalactic,
+                "ask-intergalactic",
+            ),
+            (
+                "How many Dollars is glob Gold?",
+                QueryKind::AskPriceIn,
+                "ask-price-in",
+            ),
+            ("forget glob", QueryKind::ForgetWord, "forget-word"),
+            (
+                "How many credits is glob Gold and glob Silver?",
+                QueryKind::AskTotalPrice,
+                "ask-total-price",
+            ),
+            (
+                "How many credits is glob Gold in intergalactic?",
+                QueryKind::AskPriceIntergalactic,
+                "ask-price-intergalactic",
+            ),
+            (
+                "What is the average price?",
+                QueryKind::AskAveragePrice,
+                "ask-average-price",
+            ),
+            ("forget all prices", QueryKind::ClearPrices, "clear-prices"),
+        ];
+
+        for (query, kind, name) in kinds {
+            assert_eq!(Ford::classify(query), Some(kind));
+            assert_eq!(kind.to_string(), name);
+        }
+
+        assert!(QueryKind::SetDigit.is_definition());
+        assert!(QueryKind::RenameItem.is_definition());
+        assert!(!QueryKind::AskPrice.is_definition());
+        assert!(!QueryKind::ListItems.is_definition());
+
+        assert_eq!(Ford::classify("Who is glob?"), None);
+    }
+
+    #[test]
+    fn price_range() {
+        // zero count
+        assert!(matches!(
+            unit_price_of(dec!(10), Decimal::ZERO),
+            Err(QueryError::ZeroQuantity)
+        ));
+        let price = Price {
+            total: dec!(10),
+            count: Decimal::ZERO,
+        };
+        assert!(matches!(
+            price.of(Decimal::ONE),
+            Err(QueryError::ZeroQuantity)
+        ));
+        let price = Price {
+            total: dec!(10),
+            count: Decimal::ONE,
+        };
+        assert_eq!(price.of(Decimal::ZERO).unwrap(), Decimal::ZERO);
+        // too small to be represented
+        assert!(matches!(
+            unit_price_of(Decimal::new(1, 28), dec!(3)),
+            Err(QueryError::PriceUnderflow)
+        ));
+        assert_eq!(
+            unit_price_of(Decimal::ZERO, dec!(3)).unwrap(),
+            Decimal::ZERO
+        );
+
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+
+        // near-max total
+        ford.query("glob glob Gold is 79228162514264337593543950334 Credits")
+            .unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob glob Gold?").unwrap(),
+            Some("glob glob Gold is 79228162514264337593543950334 Credits".to_string())
+        );
+        let err = ford.query("How many credits is prok Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::PriceOverflow));
+    }
+
+    #[test]
+    fn ask_word() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("tegj is L").unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.query("What is glob?").unwrap(),
+            Some("glob is I (1)".to_string())
+        );
+        assert_eq!(
+            ford.query("what is tegj ?").unwrap(),
+            Some("tegj is L (50)".to_string())
+        );
+
+        // negative tests
+        let err = ford.query("What is prok?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedWord(word) if word == "prok"));
+        assert!(ford.query("What is glob glob?").is_err());
+    }
+
+    #[test]
+    fn ask_intergalactic() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("pish is X").unwrap();
+        ford.query("tegj is L").unwrap();
+
+        // positive tests
+        assert_eq!(
+            ford.query("What is 42 in intergalactic?").unwrap(),
+            Some("42 is pish tegj glob glob".to_string())
+        );
+        assert_eq!(
+            ford.query("what is 4 in Intergalactic ?").unwrap(),
+            Some("4 is glob prok".to_string())
+        );
+
+        // negative tests
+        let err = ford.query("What is 100 in intergalactic?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::MissingDigitWord('C')));
+        let err = ford.query("What is 0 in intergalactic?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InvalidRomanNumeral(_)));
+    }
+
+    #[test]
+    fn verbose() {
+        let mut ford = Ford::new();
+
+        // silent by default
+        assert_eq!(ford.query("glob is I").unwrap(), None);
+        assert_eq!(ford.query("glob glob Gold is 20 Credits").unwrap(), None);
+
+        ford.set_verbose(true);
+        assert_eq!(
+            ford.query("prok is V").unwrap(),
+            Some("OK: prok = V".to_string())
+        );
+        assert_eq!(
+            ford.query("glob prok Iron is 8 Credits").unwrap(),
+            Some("OK: glob prok Iron = 8 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("Rename Iron to Steel").unwrap(),
+            Some("OK: Iron -> Steel".to_string())
+        );
+        // questions are answered as usual
+        assert_eq!(
+            ford.query("How much is glob prok?").unwrap(),
+            Some("glob prok is 4".to_string())
+        );
+        // failed definitions aren't acknowledged
+        assert!(ford.query("glob Gold is 30 Credits").is_err());
+
+        ford.set_verbose(false);
+        assert_eq!(ford.query("pish is X").unwrap(), None);
+    }
+
+    #[test]
+    fn validate() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob glob Silver is 34 Credits").unwrap();
+        ford.query("glob Coupon is -5 Credits").unwrap();
+        assert!(matches!(
+            ford.validate(),
+            Err(issues) if issues == vec![ValidationIssue::NegativePrice("Coupon".to_string())]
+        ));
+
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("uno"), 'I'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("foo"), 'Q'),
+        ]));
+        let price_set = HashMap::from([(Cow::from("Gold"), dec!(10))]);
+        let ford = Ford::with(lang, price_set);
+        assert_eq!(
+            ford.validate().unwrap_err(),
+            vec![
+                ValidationIssue::InvalidDigit("foo".to_string(), 'Q'),
+                ValidationIssue::DuplicateDigit('I', vec!["glob".to_string(), "uno".to_string()]),
+                ValidationIssue::MissingDigit('V'),
+            ]
+        );
+
+        let ford = Ford::with(
+            Language::new(),
+            HashMap::from([(Cow::from("Gold"), dec!(1))]),
+        );
+        assert_eq!(
+            ford.validate().unwrap_err(),
+            vec![ValidationIssue::UntranslatableAmount("Gold".to_string())]
+        );
+
+        assert!(Ford::new().validate().is_ok());
+    }
+
+    #[test]
+    fn to_script() {
+        let mut ford = Ford::new();
+        ford.query("prok is V").unwrap();
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob Silver is 34 Credits").unwrap();
+        ford.query("glob glob Soy milk is 5 Credits").unwrap();
+        ford.query("glob prok Gold is 57800 Credits").unwrap();
+
+        let script = ford.to_script();
+        assert_eq!(
+            script,
+            "glob is I\n\
+            prok is V\n\
+            glob prok Gold is 57800 Credits\n\
+            glob glob Silver is 34 Credits\n\
+            glob glob Soy milk is 5 Credits\n"
+        );
+
+        let replayed = Ford::train(&script).unwrap();
+        assert_eq!(replayed.to_script(), script);
+
+        let items = |ford: &Ford| {
+            let mut items = ford
+                .items()
+                .map(|(item, price)| (item.to_string(), price))
+                .collect::<Vec<_>>();
+            items.sort_unstable();
+            items
+        };
+        assert_eq!(items(&replayed), items(&ford));
+    }
+
+    #[test]
+    fn from_facts() {
+        let word = |word: &'static str, digit| Fact::Word {
+            word: Cow::from(word),
+            digit,
+        };
+        let item = |phrase: &'static str, item: &'static str, total| Fact::Item {
+            phrase: Cow::from(phrase),
+            item: Cow::from(item),
+            total,
+        };
+
+        let mut ford = Ford::from_facts(
+            [
+                word("glob", 'I'),
+                word("prok", 'V'),
+                item("glob glob", "Silver", dec!(34)),
+                item("glob prok", "Gold", dec!(57800)),
+            ],
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            ford.query("How many credits is prok Silver?").unwrap(),
+            Some("prok Silver is 85 Credits".to_string())
+        );
+
+        let facts = [
+            word("glob", 'I'),
+            word("glob", 'V'),
+            item("glob", "Silver", dec!(17)),
+            item("pish", "Gold", dec!(10)),
+        ];
+        let errors = Ford::from_facts(facts.clone(), false).unwrap_err();
+        assert_eq!(
+            errors.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert!(matches!(errors[0].1, QueryError::WordAlreadyExists(_)));
+        assert!(matches!(errors[1].1, QueryError::UnrecognizedWord(_)));
+
+        let errors = Ford::from_facts(facts, true).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 1);
+    }
+
+    #[test]
+    fn train() {
+        let mut ford = Ford::train(
+            "glob is I
+            prok is V
+            pish is X
+            tegj is L
+
+            glob glob Silver is 34 Credits
+            glob prok Gold is 57800 Credits
+            pish pish Iron is 3910 Credits",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ford.query("how much is pish tegj glob glob ?").unwrap(),
+            Some("pish tegj glob glob is 42".to_string())
+        );
+        assert_eq!(
+            ford.query("how many Credits is glob prok Iron ?").unwrap(),
+            Some("glob prok Iron is 782 Credits".to_string())
+        );
+
+        let errors = Ford::train("glob is I\nglob is V\nhow much is glob?\nfoo Gold is 1 Credits")
+            .unwrap_err();
+        assert_eq!(
+            errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert!(matches!(
+            errors[0].1.cause(),
+            QueryError::WordAlreadyExists(_)
+        ));
+    }
+
+    #[test]
+    fn price_format() {
+        let grouped = PriceFormat {
+            grouping: Some(','),
+            ..PriceFormat::default()
+        };
+        assert_eq!(grouped.format(dec!(57800)), "57,800 Credits");
+        assert_eq!(grouped.format(dec!(800)), "800 Credits");
+        assert_eq!(grouped.format(dec!(-1234567.891)), "-1,234,567.891 Credits");
+
+        let symbol = PriceFormat {
+            grouping: None,
+            prefix: "₡".to_string(),
+            suffix: String::new(),
+        };
+        assert_eq!(symbol.format(dec!(57800)), "₡57800");
+
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 57800 Credits").unwrap();
+        assert_eq!(
+            ford.query("How many credits is glob Gold?").unwrap(),
+            Some("glob Gold is 57800 Credits".to_string())
+        );
+
+        ford.set_price_format(grouped);
+        assert_eq!(
+            ford.query("How many credits is glob Gold?").unwrap(),
+            Some("glob Gold is 57,800 Credits".to_string())
+        );
+
+        ford.set_price_format(symbol);
+        assert_eq!(
+            ford.query("How many credits is glob Gold?").unwrap(),
+            Some("glob Gold is ₡57800".to_string())
+        );
+    }
+
+    #[test]
+    fn decimal_quantity() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+
+        assert_eq!(
+            ford.query("How many credits is 20 Gold?").unwrap(),
+            Some("20 Gold is 200 Credits".to_string())
+        );
+        assert_eq!(
+            ford.query("How much is 42?").unwrap(),
+            Some("42 is 42".to_string())
+        );
+
+        ford.set_show_roman(true);
+        assert_eq!(
+            ford.query("How much is 42 ?").unwrap(),
+            Some("42 is 42 (XLII)".to_string())
+        );
+        assert_eq!(
+            ford.query("How much is 4000?").unwrap(),
+            Some("4000 is 4000".to_string())
+        );
+
+        // the forms can't be mixed
+        assert!(ford.query("How many credits is glob 20 Gold?").is_err());
+        assert!(ford.query("How much is 2 glob?").is_err());
+
+        let err = ford.query("How much is 99999999999?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::InvalidQuantity(_)));
+
+        let err = ford.query("How much is 0?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::ZeroQuantity));
+        let err = ford.query("How many credits is 0 Gold?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::ZeroQuantity));
+    }
+
+    #[test]
+    fn list_items() {
+        let mut ford = Ford::new();
+        assert_eq!(
+            ford.query("What items do you know?").unwrap(),
+            Some("No items known yet".to_string())
+        );
+
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob Silver is 34 Credits").unwrap();
+        ford.query("glob Gold is 14450 Credits").unwrap();
+        ford.query("glob glob Iron is 391 Credits").unwrap();
+
+        assert_eq!(
+            ford.query("what items do you know ?").unwrap(),
+            Some("Gold is 14450 Credits, Iron is 195.5 Credits, Silver is 17 Credits".to_string())
+        );
+    }
+
+    #[test]
+    fn average_price() {
+        let mut ford = Ford::new();
+        assert_eq!(ford.average_price(), None);
+        let err = ford.query("What is the average price?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::NoPricesDefined));
+
+        ford.query("glob is I").unwrap();
+        ford.query("glob glob Silver is 34 Credits").unwrap();
+        ford.query("glob Gold is 13 Credits").unwrap();
+        assert_eq!(ford.average_price(), Some(Credits(dec!(15))));
+
+        ford.query("glob Iron is 1 Credits").unwrap();
+        assert_eq!(
+            ford.average_price().unwrap().amount().round_dp(4),
+            dec!(10.3333)
+        );
+        ford.query("glob Tin is 2 Credits").unwrap();
+        assert_eq!(
+            ford.query("what is the average price ?").unwrap(),
+            Some("The average price is 8.25 Credits".to_string())
+        );
+
+        // the sum of prices overflows
+        ford.query("glob Platinum is 79228162514264337593543950335 Credits")
+            .unwrap();
+        ford.query("glob Diamond is 79228162514264337593543950335 Credits")
+            .unwrap();
+        assert_eq!(ford.average_price(), None);
+        let err = ford.query("What is the average price?").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::PriceOverflow));
+    }
+
+    #[test]
+    fn forget_word() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob glob Silver is 34 Credits").unwrap();
+
+        // positive tests
+        ford.query("Forget glob").unwrap();
+        assert!(ford.query("How much is glob?").is_err());
+        ford.query("prok is unknown").unwrap();
+        assert!(ford.query("How much is prok?").is_err());
+
+        // digits are free again, and prices are kept
+        ford.query("pish is I").unwrap();
+        assert_eq!(
+            ford.query("How many credits is pish Silver?").unwrap(),
+            Some("pish Silver is 17 Credits".to_string())
+        );
+
+        // undo restores the word
+        ford.query("pish is nothing").unwrap();
+        assert!(ford.undo());
+        assert_eq!(
+            ford.query("How much is pish?").unwrap(),
+            Some("pish is 1".to_string())
+        );
+
+        // negative tests
+        let err = ford.query("forget glob").unwrap_err();
+        assert!(matches!(err.cause(), QueryError::UnrecognizedWord(_)));
+    }
+
+    #[test]
+    fn clear_prices() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob Gold is 10 Credits").unwrap();
+        ford.query("glob Silver is 4 Credits").unwrap();
+
+        ford.clear_prices();
+        assert_eq!(
+            ford.query("How much is glob prok?").unwrap(),
+            Some("glob prok is 4".to_string())
+        );
+        for item in ["Gold", "Silver"] {
+            let err = ford
+                .query(&format!("How many credits is glob {item}?"))
+                .unwrap_err();
+            assert!(matches!(err.cause(), QueryError::NoPricesDefined));
+        }
+
+        // prices can be defined again, even with a different price
+        ford.query("glob Gold is 12 Credits").unwrap();
+        assert_eq!(ford.items().count(), 1);
+
+        // as a query, and undone
+        ford.set_verbose(true);
+        assert_eq!(
+            ford.query("Forget all prices").unwrap(),
+            Some("OK: forgot all prices".to_string())
+        );
+        assert_eq!(ford.items().count(), 0);
+        assert!(ford.undo());
+        assert_eq!(
+            ford.query("How many credits is glob Gold?").unwrap(),
+            Some("glob Gold is 12 Credits".to_string())
+        );
+    }
+
+    #[test]
+    fn undo() {
+        let mut ford = Ford::new();
+        ford.query("glob is I").unwrap();
+        ford.query("prok is V").unwrap();
+        ford.query("glob glob Silver is 34 Credits").unwrap();
+
+        // queries that don't change state aren't recorded
+        ford.query("How much is glob prok?").unwrap();
+        assert!(ford.query("Who is glob?").is_err());
+
+        assert!(ford.undo());
+        assert!(ford.query("How many credits is glob Silver?").is_err());
+        assert!(ford.undo());
+        assert!(ford.query("How much is glob prok?").is_err());
+        assert_eq!(
+            ford.query("How much is glob glob?").unwrap(),
+            Some("glob glob is 2".to_string())
+        );
+
+        // the digit is free to be used again
+        ford.query("pish is V").unwrap();
+
+        assert!(ford.undo());
+        assert!(ford.undo());
+        assert!(!ford.undo());
+        assert!(ford.query("How much is glob?").is_err());
+    }
 }
@@ -0,0 +1,168 @@
+//! A typed amount of credits, the currency prices are kept in.
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use rust_decimal::Decimal;
+
+/// `Credits` is an amount of credits, such as a price, as opposed to a count of items.
+///
+/// `Credits` displays as e.g. `42 Credits`. The amount is available with `amount`, and converts
+/// from and into `Decimal` for interop. Adding or subtracting credits gives credits, while
+/// scaling them by a `Decimal` gives credits and dividing credits by credits gives a `Decimal`.
+///
+/// # Examples
+/// ```
+/// use rust_decimal_macros::dec;
+/// use intra::Credits;
+///
+/// let price = Credits::from(dec!(17));
+/// let total = price * dec!(2) + Credits::from(dec!(8));
+/// assert_eq!(total.to_string(), "42 Credits");
+/// assert_eq!(total / price, dec!(42) / dec!(17));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Credits(pub Decimal);
+
+impl Credits {
+    /// No credits.
+    pub const ZERO: Credits = Credits(Decimal::ZERO);
+
+    /// Returns the amount of credits.
+    pub fn amount(self) -> Decimal {
+        self.0
+    }
+
+    /// Adds credits, returning `None` on overflow.
+    pub fn checked_add(self, other: Credits) -> Option<Credits> {
+        self.0.checked_add(other.0).map(Credits)
+    }
+
+    /// Subtracts credits, returning `None` on overflow.
+    pub fn checked_sub(self, other: Credits) -> Option<Credits> {
+        self.0.checked_sub(other.0).map(Credits)
+    }
+
+    /// Scales credits by a factor, returning `None` on overflow.
+    pub fn checked_mul(self, factor: Decimal) -> Option<Credits> {
+        self.0.checked_mul(factor).map(Credits)
+    }
+}
+
+impl fmt::Display for Credits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} Credits", self.0)
+    }
+}
+
+impl From<Decimal> for Credits {
+    fn from(amount: Decimal) -> Self {
+        Self(amount)
+    }
+}
+
+impl From<Credits> for Decimal {
+    fn from(credits: Credits) -> Self {
+        credits.0
+    }
+}
+
+impl PartialEq<Decimal> for Credits {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Add for Credits {
+    type Output = Credits;
+
+    fn add(self, other: Credits) -> Credits {
+        Credits(self.0 + other.0)
+    }
+}
+
+impl Sub for Credits {
+    type Output = Credits;
+
+    fn sub(self, other: Credits) -> Credits {
+        Credits(self.0 - other.0)
+    }
+}
+
+impl Neg for Credits {
+    type Output = Credits;
+
+    fn neg(self) -> Credits {
+        Credits(-self.0)
+    }
+}
+
+impl Mul<Decimal> for Credits {
+    type Output = Credits;
+
+    fn mul(self, factor: Decimal) -> Credits {
+        Credits(self.0 * factor)
+    }
+}
+
+impl Div<Decimal> for Credits {
+    type Output = Credits;
+
+    fn div(self, divisor: Decimal) -> Credits {
+        Credits(self.0 / divisor)
+    }
+}
+
+/// The ratio of two amounts of credits.
+impl Div for Credits {
+    type Output = Decimal;
+
+    fn div(self, divisor: Credits) -> Decimal {
+        self.0 / divisor.0
+    }
+}
+
+impl Sum for Credits {
+    fn sum<I: Iterator<Item = Credits>>(iter: I) -> Credits {
+        iter.fold(Credits::ZERO, Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let ten = Credits::from(dec!(10));
+        let two = Credits(dec!(2.5));
+
+        // positive tests
+        assert_eq!(ten + two, dec!(12.5));
+        assert_eq!(ten - two, Credits(dec!(7.5)));
+        assert_eq!(-two, dec!(-2.5));
+        assert_eq!(two * dec!(4), ten);
+        assert_eq!(ten / dec!(4), two);
+        assert_eq!(ten / two, dec!(4));
+        assert_eq!([ten, two, two].into_iter().sum::<Credits>(), dec!(15));
+        assert_eq!(Decimal::from(ten), dec!(10));
+        assert_eq!(ten.amount(), dec!(10));
+        assert!(two < ten);
+
+        // negative tests
+        let max = Credits(Decimal::MAX);
+        assert!(max.checked_add(ten).is_none());
+        assert!((-max).checked_sub(ten).is_none());
+        assert!(max.checked_mul(dec!(2)).is_none());
+        assert_eq!(ten.checked_add(two), Some(Credits(dec!(12.5))));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Credits(dec!(42)).to_string(), "42 Credits");
+        assert_eq!(Credits(dec!(-2.5)).to_string(), "-2.5 Credits");
+        assert_eq!(Credits::ZERO.to_string(), "0 Credits");
+    }
+}
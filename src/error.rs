@@ -6,7 +6,7 @@ use thiserror::Error;
 #[error("String is not a valid roman numeral")]
 pub struct InvalidRomanNumeral;
 
-/// `TranslationError` represents an error that results from not recognizing a word or phrase.
+/// `QueryError` represents an error that results from not recognizing a word or phrase.
 #[derive(Debug, Error)]
 pub enum QueryError {
     #[allow(missing_docs)]
@@ -27,9 +27,144 @@ pub enum QueryError {
     #[allow(missing_docs)]
     #[error("Digit already exists: `{0}`")]
     DigitAlreadyExists(char),
+    /// A lowercase word where an item was expected, likely an item that isn't capitalized.
+    #[error("Unrecognized item: `{0}`, item names must be capitalized")]
+    LowercaseItem(String),
     #[allow(missing_docs)]
     #[error("Item already exists: `{0}`")]
     ItemAlreadyExists(String),
+    #[allow(missing_docs)]
+    #[error("Invalid quantity: `{0}`")]
+    InvalidQuantity(String),
+    #[allow(missing_docs)]
+    #[error("Inconsistent price for item: `{0}`")]
+    InconsistentPrice(String),
+    #[allow(missing_docs)]
+    #[error("Item doesn't have a positive price: `{0}`")]
+    NonPositivePrice(String),
+    #[allow(missing_docs)]
+    #[error("Unrecognized currency: `{0}`")]
+    UnrecognizedCurrency(String),
+    #[allow(missing_docs)]
+    #[error("Currency doesn't have a positive rate: `{0}`")]
+    NonPositiveRate(String),
+    #[allow(missing_docs)]
+    #[error("Invalid price: `{0}`")]
+    InvalidPrice(String),
+    #[allow(missing_docs)]
+    #[error("Price isn't a whole number: `{0}`")]
+    FractionalPrice(String),
+    #[allow(missing_docs)]
+    #[error("Quantity is zero")]
+    ZeroQuantity,
+    #[allow(missing_docs)]
+    #[error("Price is too large")]
+    PriceOverflow,
+    #[allow(missing_docs)]
+    #[error("Price is too small")]
+    PriceUnderflow,
+    #[allow(missing_docs)]
+    #[error("No prices have been defined yet")]
+    NoPricesDefined,
+    #[allow(missing_docs)]
+    #[error("No word for digit `{0}`")]
+    MissingDigitWord(char),
+    #[allow(missing_docs)]
+    #[error("Phrase is longer than {0} words")]
+    PhraseTooLong(usize),
+    #[allow(missing_docs)]
+    #[error("Malformed line: `{0}`")]
+    MalformedLine(String),
+    /// An error together with the query that caused it.
+    #[error("{source} in query `{query}`")]
+    InQuery {
+        /// The offending query.
+        query: String,
+        /// The underlying error.
+        source: Box<QueryError>,
+    },
+}
+
+impl QueryError {
+    /// Attach the query that caused this error.
+    ///
+    /// Errors that already carry the query are returned unchanged.
+    pub fn with_query(self, query: &str) -> Self {
+        match self {
+            Self::UnrecognizedQuery(_) | Self::InQuery { .. } => self,
+            source => Self::InQuery {
+                query: query.to_string(),
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Returns the query that caused this error, if known.
+    pub fn query(&self) -> Option<&str> {
+        match self {
+            Self::UnrecognizedQuery(query) | Self::InQuery { query, .. } => Some(query),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error, without any query context.
+    pub fn cause(&self) -> &QueryError {
+        match self {
+            Self::InQuery { source, .. } => source.cause(),
+            _ => self,
+        }
+    }
+}
+
+/// `TranslationError` is a `QueryError` for translations that borrows from the translated text
+/// instead of allocating.
+#[derive(Debug, Error)]
+pub enum TranslationError<'t> {
+    #[allow(missing_docs)]
+    #[error("{0}")]
+    InvalidRomanNumeral(InvalidRomanNumeral),
+    #[allow(missing_docs)]
+    #[error("Unrecognized word: `{0}`")]
+    UnrecognizedWord(&'t str),
+    #[allow(missing_docs)]
+    #[error("No word for digit `{0}`")]
+    MissingDigitWord(char),
+}
+
+impl<'t> From<InvalidRomanNumeral> for TranslationError<'t> {
+    fn from(err: InvalidRomanNumeral) -> Self {
+        Self::InvalidRomanNumeral(err)
+    }
+}
+
+impl<'t> From<TranslationError<'t>> for QueryError {
+    fn from(err: TranslationError<'t>) -> Self {
+        match err {
+            TranslationError::InvalidRomanNumeral(err) => Self::InvalidRomanNumeral(err),
+            TranslationError::UnrecognizedWord(word) => Self::UnrecognizedWord(word.to_string()),
+            TranslationError::MissingDigitWord(digit) => Self::MissingDigitWord(digit),
+        }
+    }
+}
+
+/// `ValidationIssue` is a problem with the words and prices known to a `Ford`.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidationIssue {
+    #[allow(missing_docs)]
+    #[error("Word `{0}` isn't a roman digit: `{1}`")]
+    InvalidDigit(String, char),
+    /// Several words for the same digit, sorted.
+    #[error("Digit `{0}` has several words: {1:?}")]
+    DuplicateDigit(char, Vec<String>),
+    /// A digit smaller than the largest known digit has no word.
+    #[error("Digit `{0}` has no word")]
+    MissingDigit(char),
+    #[allow(missing_docs)]
+    #[error("Item has a negative price: `{0}`")]
+    NegativePrice(String),
+    /// The amount an item was defined with can't be written with the known words.
+    #[error("Amount of item can't be translated: `{0}`")]
+    UntranslatableAmount(String),
 }
 
 impl From<InvalidRomanNumeral> for QueryError {
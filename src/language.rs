@@ -1,18 +1,443 @@
 //! Language mapping and translator from the intergalactic numeric system to Roman numerals.
-use std::borrow::Cow;
+use std::borrow::{Borrow, Cow};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, BufRead, Write};
+use std::mem;
+use std::sync::Mutex;
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
+use lazy_static::lazy_static;
+use regex::Regex;
+use rust_decimal::Decimal;
 
-use crate::error::QueryError;
-use crate::roman::Roman;
+use crate::error::{InvalidRomanNumeral, QueryError, TranslationError};
+use crate::quantity::Quantity;
+use crate::roman::{Roman, SubtractiveRules};
+
+lazy_static! {
+    static ref WORD: Regex = Regex::new(r"\w+").unwrap();
+}
+
+/// Returns the number of single character insertions, deletions and substitutions
+/// needed to turn `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    // distances from the prefix of `a` seen so far to every prefix of `b`
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// A step-by-step translation of an intergalactic numeral, see `Language::explain`.
+///
+/// Displays as one `word -> digit` line per word, followed by the numeral and its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'t> {
+    /// Every word of the numeral with its digit, in order.
+    pub words: Vec<(&'t str, char)>,
+    /// The assembled numeral.
+    pub roman: Roman,
+}
+
+impl<'t> Explanation<'t> {
+    /// Returns the value of the numeral.
+    pub fn value(&self) -> Quantity {
+        Quantity::from(&self.roman)
+    }
+}
+
+impl<'t> fmt::Display for Explanation<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (word, digit) in &self.words {
+            writeln!(f, "{word} -> {digit}")?;
+        }
+        write!(f, "{} = {}", self.roman, self.value())
+    }
+}
+
+/// Translate a phrase in one dialect to the phrase with the same value in another.
+///
+/// The phrase is translated to a number with `a`, which is written with the words of `b`.
+/// If several words of `b` map to the same digit, the alphabetically first one is used.
+///
+/// # Examples
+/// ```
+/// use intra::language::{translate_between, Language};
+///
+/// let mut a = Language::new();
+/// a.insert("glob", 'I');
+/// a.insert("pish", 'X');
+/// let mut b = Language::new();
+/// b.insert("uno", 'I');
+/// b.insert("diez", 'X');
+///
+/// assert_eq!(translate_between(&a, &b, "pish glob").unwrap(), "diez uno");
+/// ```
+pub fn translate_between<'t>(
+    a: &Language<'_>,
+    b: &Language<'_>,
+    phrase: &'t str,
+) -> Result<String, TranslationError<'t>> {
+    let roman = a.translate_ref(phrase)?;
+
+    roman
+        .to_string()
+        .chars()
+        .map(|digit| {
+            b.word_for(digit)
+                .ok_or(TranslationError::MissingDigitWord(digit))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|words| words.join(" "))
+}
+
+/// A piece of a text split by `InlineTranslator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'t> {
+    /// Text that isn't a numeral, copied as is.
+    Text(&'t str),
+    /// A run of known words forming a valid numeral.
+    Numeral {
+        /// The words of the numeral, with the whitespace between them.
+        text: &'t str,
+        /// The value of the numeral.
+        value: Quantity,
+    },
+}
+
+/// Displays text as is and numerals as their decimal value.
+impl<'t> fmt::Display for Segment<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => f.write_str(text),
+            Self::Numeral { value, .. } => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Splits a text into plain text and intergalactic numerals, as an iterator of `Segment`s.
+///
+/// This is the lazy version of `Language::translate_inline`, which leaves formatting the
+/// numerals to the caller. Numerals are found as the iterator advances,
+/// and segments borrow from the text.
+///
+/// # Examples
+/// ```
+/// use intra::language::{InlineTranslator, Language, Segment};
+///
+/// let mut lang = Language::new();
+/// lang.insert("glob", 'I');
+/// lang.insert("pish", 'X');
+///
+/// let marked = InlineTranslator::new(&lang, "pish glob apples")
+///     .map(|segment| match segment {
+///         Segment::Numeral { value, .. } => format!("<{value}>"),
+///         Segment::Text(text) => text.to_string(),
+///     })
+///     .collect::<String>();
+/// assert_eq!(marked, "<11> apples");
+/// ```
+#[derive(Debug, Clone)]
+pub struct InlineTranslator<'a> {
+    language: &'a Language<'a>,
+    text: &'a str,
+    // end of the text already yielded
+    yielded: usize,
+    // where to look for the next word
+    scan: usize,
+    // a numeral found after some text, yielded after the text
+    pending: Option<Segment<'a>>,
+}
+
+impl<'a> InlineTranslator<'a> {
+    /// Construct a translator for the numerals of `language` in `text`.
+    pub fn new(language: &'a Language<'a>, text: &'a str) -> Self {
+        Self {
+            language,
+            text,
+            yielded: 0,
+            scan: 0,
+            pending: None,
+        }
+    }
+
+    /// Finds the next run of known words that forms a valid numeral.
+    ///
+    /// A run is a sequence of known words separated only by whitespace.
+    fn next_numeral(&mut self) -> Option<(usize, usize, Quantity)> {
+        // start and end of the current run of known words, and the words themselves
+        let mut run: Option<(usize, usize, Vec<&str>)> = None;
+
+        loop {
+            let word = WORD.find_at(self.text, self.scan);
+
+            if let (Some(word), Some((_, end, words))) = (word, &mut run) {
+                let known = self.language.contains(word.as_str());
+                if known && self.text[*end..word.start()].trim().is_empty() {
+                    *end = word.end();
+                    words.push(word.as_str());
+                    self.scan = word.end();
+                    continue;
+                }
+            }
+
+            // the run ends before `word`, which is looked at again without a run
+            if let Some((start, end, words)) = run.take() {
+                if let Ok(roman) = self.language.translate(&words.join(" ")) {
+                    return Some((start, end, Quantity::from(roman)));
+                }
+                continue;
+            }
+
+            let word = word?;
+            self.scan = word.end();
+            if self.language.contains(word.as_str()) {
+                run = Some((word.start(), word.end(), vec![word.as_str()]));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for InlineTranslator<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Segment<'a>> {
+        if let Some(segment) = self.pending.take() {
+            return Some(segment);
+        }
+
+        match self.next_numeral() {
+            Some((start, end, value)) => {
+                let numeral = Segment::Numeral {
+                    text: &self.text[start..end],
+                    value,
+                };
+                let before = &self.text[self.yielded..start];
+                self.yielded = end;
+
+                if before.is_empty() {
+                    Some(numeral)
+                } else {
+                    self.pending = Some(numeral);
+                    Some(Segment::Text(before))
+                }
+            }
+            None if self.yielded < self.text.len() => {
+                let rest = &self.text[self.yielded..];
+                self.yielded = self.text.len();
+                Some(Segment::Text(rest))
+            }
+            None => None,
+        }
+    }
+}
+
+/// A least recently used cache of translated phrases, see `Language::with_cache`.
+///
+/// Entries are kept in a list ordered by their last use, so lookups and evictions take
+/// constant time. Caches never affect equality of languages.
+#[derive(Debug, Default)]
+struct TranslationCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+}
+
+/// The cached phrases, as a doubly linked list stored in a `Vec`.
+#[derive(Debug, Default)]
+struct CacheEntries {
+    // position of every cached phrase in `nodes`
+    index: HashMap<String, usize>,
+    nodes: Vec<CacheNode>,
+    // most and least recently used nodes
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+#[derive(Debug)]
+struct CacheNode {
+    text: String,
+    roman: Roman,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl CacheEntries {
+    /// Takes a node out of the list, leaving it in `nodes`.
+    fn unlink(&mut self, node: usize) {
+        let CacheNode { prev, next, .. } = self.nodes[node];
+        match prev {
+            Some(prev) => self.nodes[prev].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.nodes[next].prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    /// Puts an unlinked node at the front of the list, as the most recently used.
+    fn push_front(&mut self, node: usize) {
+        self.nodes[node].prev = None;
+        self.nodes[node].next = self.head;
+        match self.head {
+            Some(head) => self.nodes[head].prev = Some(node),
+            None => self.tail = Some(node),
+        }
+        self.head = Some(node);
+    }
+
+    /// Marks a node as the most recently used.
+    fn touch(&mut self, node: usize) {
+        if self.head != Some(node) {
+            self.unlink(node);
+            self.push_front(node);
+        }
+    }
+}
+
+impl TranslationCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the cached translation of a phrase, marking it as recently used.
+    fn get(&self, text: &str) -> Option<Roman> {
+        let mut entries = self.entries.lock().ok()?;
+        let node = *entries.index.get(text)?;
+        entries.touch(node);
+        Some(entries.nodes[node].roman.clone())
+    }
+
+    /// Caches the translation of a phrase, evicting the least recently used one if full.
+    fn insert(&self, text: &str, roman: &Roman) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        if let Some(&node) = entries.index.get(text) {
+            entries.nodes[node].roman = roman.clone();
+            entries.touch(node);
+            return;
+        }
+
+        let cached = CacheNode {
+            text: text.to_string(),
+            roman: roman.clone(),
+            prev: None,
+            next: None,
+        };
+        let node = match entries.tail {
+            // the evicted node's slot is reused
+            Some(oldest) if entries.index.len() >= self.capacity => {
+                entries.unlink(oldest);
+                let evicted = mem::replace(&mut entries.nodes[oldest], cached);
+                entries.index.remove(&evicted.text);
+                oldest
+            }
+            _ => {
+                entries.nodes.push(cached);
+                entries.nodes.len() - 1
+            }
+        };
+        entries.index.insert(text.to_string(), node);
+        entries.push_front(node);
+    }
+
+    /// Returns the number of cached phrases.
+    fn len(&self) -> usize {
+        self.entries.lock().map_or(0, |entries| entries.index.len())
+    }
+
+    /// Forgets every cached phrase.
+    fn clear(&mut self) {
+        if let Ok(entries) = self.entries.get_mut() {
+            *entries = CacheEntries::default();
+        }
+    }
+}
+
+/// Clones start with an empty cache of the same capacity.
+impl Clone for TranslationCache {
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
 
 /// `Language` is a mapping of intergalactic numerals to terran Roman numerals.
-#[derive(Default, Debug, Clone)]
+///
+/// `Language` is `Send` and `Sync`, so a read-only `Language` can be shared between threads.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use intra::language::Language;
+///
+/// let mut lang = Language::new();
+/// lang.insert("glob", 'I');
+/// lang.insert("pish", 'X');
+/// let lang = Arc::new(lang);
+///
+/// let handles = (0..4)
+///     .map(|_| {
+///         let lang = Arc::clone(&lang);
+///         thread::spawn(move || u32::from(lang.translate("pish glob glob").unwrap()))
+///     })
+///     .collect::<Vec<_>>();
+///
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap(), 12);
+/// }
+/// ```
+#[derive(Debug, Clone)]
 pub struct Language<'a> {
     map: HashMap<Cow<'a, str>, char>,
+    max_tokens: usize,
+    subtractive_rules: SubtractiveRules,
+    cache: Option<TranslationCache>,
+}
+
+impl<'a> PartialEq for Language<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+            && self.max_tokens == other.max_tokens
+            && self.subtractive_rules == other.subtractive_rules
+    }
+}
+
+impl<'a> Eq for Language<'a> {}
+
+impl<'a> Default for Language<'a> {
+    fn default() -> Self {
+        Self::with(HashMap::new())
+    }
 }
 
 impl<'a> Language<'a> {
+    /// The default maximum number of words in a translated phrase.
+    pub const DEFAULT_MAX_TOKENS: usize = 1024;
+
     /// Construct an empty `Language`.
     pub fn new() -> Self {
         Self::default()
@@ -20,7 +445,60 @@ impl<'a> Language<'a> {
 
     /// Construct a `Language` with an existing map.
     pub fn with(map: HashMap<Cow<'a, str>, char>) -> Self {
-        Self { map }
+        Self {
+            map,
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            subtractive_rules: SubtractiveRules::default(),
+            cache: None,
+        }
+    }
+
+    /// Construct an empty `Language` that caches up to `capacity` translated phrases.
+    ///
+    /// `translate` answers repeated phrases from the cache, evicting the least recently used
+    /// phrase when it's full. The cache is cleared whenever the words or rules change.
+    pub fn with_cache(capacity: usize) -> Self {
+        Self {
+            cache: Some(TranslationCache::new(capacity)),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of phrases in the translation cache, or 0 without a cache.
+    pub fn cached_len(&self) -> usize {
+        self.cache.as_ref().map_or(0, TranslationCache::len)
+    }
+
+    /// Clears the translation cache, after anything that could change a translation.
+    fn invalidate(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Set the maximum number of words `translate` accepts in a phrase.
+    ///
+    /// Longer phrases are rejected with `QueryError::PhraseTooLong` as soon as the limit is
+    /// exceeded, without translating the rest of the phrase.
+    pub fn set_max_tokens(&mut self, max_tokens: usize) {
+        self.max_tokens = max_tokens;
+        self.invalidate();
+    }
+
+    /// Returns the maximum number of words `translate` accepts in a phrase.
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Set which subtractive pairs translated numerals may use. Defaults to the classic pairs.
+    pub fn set_subtractive_rules(&mut self, rules: SubtractiveRules) {
+        self.subtractive_rules = rules;
+        self.invalidate();
+    }
+
+    /// Returns which subtractive pairs translated numerals may use.
+    pub fn subtractive_rules(&self) -> &SubtractiveRules {
+        &self.subtractive_rules
     }
 
     /// Returns an iterator over all known digits.
@@ -28,12 +506,181 @@ impl<'a> Language<'a> {
         self.map.values().copied()
     }
 
+    /// Returns the distinct known digits, ordered by their value.
+    ///
+    /// Digits that aren't roman digits come last, in character order.
+    pub fn known_digits_sorted(&self) -> Vec<char> {
+        let mut digits = self
+            .known_digits()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        digits
+            .sort_unstable_by_key(|&digit| (Roman::digit_value(digit).unwrap_or(u32::MAX), digit));
+        digits
+    }
+
+    /// Returns the largest value that can be expressed with the known digits, or 0 if none can.
+    pub fn coverable_max(&self) -> u32 {
+        let digits = self.known_digits().collect::<HashSet<_>>();
+
+        (1..=3999)
+            .rev()
+            .find(|&value| {
+                Roman::try_from(value)
+                    .map(|roman| roman.to_string().chars().all(|c| digits.contains(&c)))
+                    .unwrap_or(false)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Returns all word-digit pairings, sorted by word.
+    pub fn to_sorted_vec(&self) -> Vec<(&str, char)> {
+        let mut pairs = self
+            .map
+            .iter()
+            .map(|(word, &digit)| (word.as_ref(), digit))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Returns a hash of the word-digit pairings, e.g. for use as a cache key.
+    ///
+    /// The pairings are hashed in sorted order with FNV-1a, so equal languages have equal
+    /// fingerprints regardless of insertion order, across runs and platforms.
+    pub fn fingerprint(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        self.to_sorted_vec()
+            .into_iter()
+            .flat_map(|(word, digit)| {
+                // the separator can't appear in a word, so pairings can't run together
+                word.bytes()
+                    .chain([0xff])
+                    .chain(u32::from(digit).to_le_bytes())
+            })
+            .fold(OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            })
+    }
+
     /// Insert a new word-digit pairing.
     pub fn insert<S>(&mut self, word: S, digit: char)
     where
         Cow<'a, str>: From<S>,
     {
         self.map.insert(From::from(word), digit);
+        self.invalidate();
+    }
+
+    /// Returns the digit of a word, inserting the digit returned by `f` if the word is unknown.
+    pub fn get_or_insert_with<S, F>(&mut self, word: S, f: F) -> char
+    where
+        Cow<'a, str>: From<S>,
+        F: FnOnce() -> char,
+    {
+        let word = From::from(word);
+        if let Some(&digit) = self.map.get(&word) {
+            return digit;
+        }
+
+        let digit = f();
+        self.insert::<Cow<'a, str>>(word, digit);
+        digit
+    }
+
+    /// Insert many word-digit pairings at once.
+    ///
+    /// If a word is already known, or appears multiple times, the last digit wins.
+    pub fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Cow<'a, str>, char)>,
+    {
+        self.map.extend(iter);
+        self.invalidate();
+    }
+
+    /// Insert several synonymous words for the same digit.
+    ///
+    /// Nothing is inserted if `digit` isn't a roman digit.
+    pub fn define_digit(
+        &mut self,
+        digit: char,
+        words: &[&'a str],
+    ) -> Result<(), InvalidRomanNumeral> {
+        if Roman::digit_value(digit).is_none() {
+            return Err(InvalidRomanNumeral);
+        }
+
+        self.extend(words.iter().map(|&word| (Cow::from(word), digit)));
+
+        Ok(())
+    }
+
+    /// Remove a word, returning its digit if it was known.
+    pub fn remove(&mut self, word: &str) -> Option<char> {
+        self.invalidate();
+        self.map.remove(word)
+    }
+
+    /// Map every word for the digit `from` to the digit `to` instead.
+    ///
+    /// Returns how many words changed. Nothing changes if `to` isn't a roman digit.
+    pub fn remap_digit(&mut self, from: char, to: char) -> usize {
+        if Roman::digit_value(to).is_none() || from == to {
+            return 0;
+        }
+
+        self.invalidate();
+        self.map
+            .values_mut()
+            .filter(|digit| **digit == from)
+            .map(|digit| *digit = to)
+            .count()
+    }
+
+    /// Shrink the capacity of the underlying map as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Returns the digit of a word, if it's known.
+    pub fn get(&self, word: &str) -> Option<char> {
+        self.map.get(word).copied()
+    }
+
+    /// Returns the known word closest to `word`, as a suggestion for a misspelled word.
+    ///
+    /// Words are compared by edit distance, and only words at most 2 edits away,
+    /// and fewer edits than the length of `word`, are suggested.
+    /// Ties are broken alphabetically.
+    pub fn closest_word(&self, word: &str) -> Option<&str> {
+        const MAX_DISTANCE: usize = 2;
+
+        let max_distance = MAX_DISTANCE.min(word.chars().count().saturating_sub(1));
+
+        self.map
+            .keys()
+            .map(|known| (levenshtein(word, known), known.as_ref()))
+            .filter(|&(distance, _)| distance <= max_distance)
+            .min()
+            .map(|(_, known)| known)
+    }
+
+    /// Returns the known words starting with `prefix`, sorted, e.g. for tab-completion.
+    ///
+    /// The comparison is case-sensitive.
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<&str> {
+        let mut words = self
+            .map
+            .keys()
+            .map(|word| word.as_ref())
+            .filter(|word| word.starts_with(prefix))
+            .collect::<Vec<_>>();
+        words.sort_unstable();
+        words
     }
 
     /// Checks if a word is known.
@@ -42,22 +689,322 @@ impl<'a> Language<'a> {
     }
 
     /// Translate an intergalactic numeral to `Roman`.
+    ///
+    /// The numeral is always in the standard form of its value: phrases accepted under relaxed
+    /// subtractive rules are rewritten with the classic pairs, so with `IL` allowed,
+    /// a phrase for `IL` translates to `XLIX`.
+    ///
+    /// With a cache, see `with_cache`, successful translations are cached by phrase.
     pub fn translate(&self, text: &str) -> Result<Roman, QueryError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.translate_uncached(text),
+        };
+
+        if let Some(roman) = cache.get(text) {
+            return Ok(roman);
+        }
+
+        let roman = self.translate_uncached(text)?;
+        cache.insert(text, &roman);
+        Ok(roman)
+    }
+
+    fn translate_uncached(&self, text: &str) -> Result<Roman, QueryError> {
         let text = text
             // split at whitespace
             .split(char::is_whitespace)
             // for every word
-            .map(|word| {
+            .enumerate()
+            .map(|(index, word)| {
+                // give up early on overly long phrases
+                if index >= self.max_tokens {
+                    return Err(QueryError::PhraseTooLong(self.max_tokens));
+                }
                 // find the translation
                 self.map
                     .get(&Cow::from(word))
                     // error if not found
                     .ok_or_else(|| QueryError::UnrecognizedWord(word.to_string()))
+                    .copied()
             })
             // collect into string or first error
             .collect::<Result<String, QueryError>>();
         // construct a roman numeral
-        text.and_then(|text| Ok(Roman::try_from(text)?))
+        text.and_then(|text| Ok(Roman::parse_with(&text, &self.subtractive_rules)?))
+    }
+
+    /// Price an intergalactic amount of an item, given the prices of single units.
+    ///
+    /// The prices may be keyed by anything that borrows as `str`, such as `&str`,
+    /// `String` or `Cow<str>`.
+    pub fn query<K>(
+        &self,
+        amount: &str,
+        item: &str,
+        price_set: &HashMap<K, Decimal>,
+    ) -> Result<Decimal, QueryError>
+    where
+        K: Borrow<str> + Eq + Hash,
+    {
+        let count = Decimal::from(u32::from(self.translate(amount)?));
+        let price = price_set
+            .get(item)
+            .ok_or_else(|| QueryError::UnrecognizedItem(item.to_string()))?;
+
+        count.checked_mul(*price).ok_or(QueryError::PriceOverflow)
+    }
+
+    /// Translate an intergalactic numeral written without spaces between words to `Roman`.
+    ///
+    /// The text is split into known words, preferring the longest word at every position and
+    /// falling back to shorter ones if the rest can't be split. So `globglob` is split into
+    /// `glob` and `glob`, and if both `ka` and `kal` are known, `kalka` is split into `kal` and `ka`.
+    pub fn translate_concatenated(&self, text: &str) -> Result<Roman, QueryError> {
+        let text = text.trim();
+        let digits = self
+            .split_concatenated(text)
+            .ok_or_else(|| QueryError::UnrecognizedWord(text.to_string()))?;
+
+        let digits = digits.into_iter().collect::<String>();
+        Ok(Roman::parse_with(&digits, &self.subtractive_rules)?)
+    }
+
+    /// Checks if a numeral written without spaces can be split into known words in more than one way.
+    ///
+    /// `translate_concatenated` picks one of the splits, callers that can't accept a guess
+    /// should reject ambiguous numerals instead.
+    pub fn is_ambiguous(&self, concatenated: &str) -> bool {
+        let text = concatenated.trim();
+
+        // the number of splits of every suffix, computed backwards and capped at 2
+        let mut splits = vec![0u8; text.len() + 1];
+        splits[text.len()] = 1;
+
+        for start in (0..text.len()).rev().filter(|&i| text.is_char_boundary(i)) {
+            splits[start] = self
+                .map
+                .keys()
+                .filter(|word| !word.is_empty() && text[start..].starts_with(word.as_ref()))
+                .map(|word| splits[start + word.len()])
+                .fold(0, |total, count| total.saturating_add(count).min(2));
+        }
+
+        !text.is_empty() && splits[0] > 1
+    }
+
+    /// Splits a text into the digits of known words, longest words first.
+    fn split_concatenated(&self, text: &str) -> Option<Vec<char>> {
+        // the word chosen at every byte index, computed backwards to avoid exponential backtracking
+        let mut chosen: Vec<Option<(usize, char)>> = vec![None; text.len() + 1];
+        let splittable = |chosen: &[Option<(usize, char)>], end: usize| {
+            end == text.len() || chosen[end].is_some()
+        };
+
+        for start in (0..text.len()).rev().filter(|&i| text.is_char_boundary(i)) {
+            chosen[start] = self
+                .map
+                .iter()
+                .filter(|(word, _)| !word.is_empty() && text[start..].starts_with(word.as_ref()))
+                .filter(|(word, _)| splittable(&chosen, start + word.len()))
+                .max_by_key(|(word, _)| word.len())
+                .map(|(word, &digit)| (word.len(), digit));
+        }
+
+        let mut digits = Vec::new();
+        let mut start = 0;
+        while start < text.len() {
+            let (len, digit) = chosen[start]?;
+            digits.push(digit);
+            start += len;
+        }
+
+        Some(digits)
+    }
+
+    /// Translate an intergalactic numeral to `Roman`, without allocating on errors.
+    ///
+    /// Like `translate`, the numeral is in standard form.
+    /// The returned error borrows the unrecognized word from `text`.
+    pub fn translate_ref<'t>(&self, text: &'t str) -> Result<Roman, TranslationError<'t>> {
+        let mut digits = [0; Roman::MAX_LEN];
+        let mut len = 0;
+
+        for word in text.split(char::is_whitespace) {
+            let digit = self
+                .map
+                .get(word)
+                .ok_or(TranslationError::UnrecognizedWord(word))?;
+
+            // keep looking for unknown words, even if the numeral is already too long
+            if let Some(slot) = digits.get_mut(len) {
+                *slot = u8::try_from(*digit).map_err(|_| InvalidRomanNumeral)?;
+            }
+            len += 1;
+        }
+
+        let digits = digits.get(..len).ok_or(InvalidRomanNumeral)?;
+        let digits = std::str::from_utf8(digits).map_err(|_| InvalidRomanNumeral)?;
+
+        Ok(Roman::parse_with(digits, &self.subtractive_rules)?)
+    }
+
+    /// Translate a `Roman` back to an intergalactic numeral.
+    ///
+    /// If several words map to the same digit, the alphabetically first one is used.
+    /// Fails with `QueryError::MissingDigitWord` if a digit of the numeral has no word.
+    pub fn to_intergalactic(&self, roman: &Roman) -> Result<String, QueryError> {
+        roman
+            .to_string()
+            .chars()
+            .map(|digit| {
+                self.word_for(digit)
+                    .ok_or(QueryError::MissingDigitWord(digit))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|words| words.join(" "))
+    }
+
+    /// Returns the alphabetically first word for a digit.
+    fn word_for(&self, digit: char) -> Option<&str> {
+        self.map
+            .iter()
+            .filter(|(_, &known)| known == digit)
+            .map(|(word, _)| word.as_ref())
+            .min()
+    }
+
+    /// Translate an intergalactic numeral to `Roman`, keeping the digit of every word.
+    pub fn explain<'t>(&self, text: &'t str) -> Result<Explanation<'t>, TranslationError<'t>> {
+        let words = text
+            .split(char::is_whitespace)
+            .map(|word| {
+                self.get(word)
+                    .map(|digit| (word, digit))
+                    .ok_or(TranslationError::UnrecognizedWord(word))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let roman = self.translate_ref(text)?;
+
+        Ok(Explanation { words, roman })
+    }
+
+    /// Checks if a phrase consists of known words forming a valid roman numeral.
+    pub fn is_valid_phrase(&self, text: &str) -> bool {
+        self.translate(text).is_ok()
+    }
+
+    /// Replace every intergalactic numeral in a text with its decimal value.
+    ///
+    /// A numeral is a run of known words separated only by whitespace.
+    /// Runs that don't form a valid roman numeral and all other text are left untouched.
+    pub fn translate_inline(&self, text: &str) -> String {
+        InlineTranslator::new(self, text).fold(
+            String::with_capacity(text.len()),
+            |mut output, segment| {
+                match segment {
+                    Segment::Text(text) => output.push_str(text),
+                    Segment::Numeral { value, .. } => output.push_str(&value.to_string()),
+                }
+                output
+            },
+        )
+    }
+
+    /// Translate a single intergalactic word to `Roman`.
+    ///
+    /// Equivalent to `translate` for one-word phrases, but skips splitting the phrase.
+    pub fn translate_word(&self, word: &str) -> Result<Roman, QueryError> {
+        let digit = self
+            .map
+            .get(word)
+            .ok_or_else(|| QueryError::UnrecognizedWord(word.to_string()))?;
+        Ok(Roman::from_digit(*digit).ok_or(InvalidRomanNumeral)?)
+    }
+
+    /// Serialize to a plain text format with one `word digit` pair per line, sorted by word.
+    pub fn to_text(&self) -> String {
+        self.to_sorted_vec()
+            .into_iter()
+            .map(|(word, digit)| format!("{word} {digit}\n"))
+            .collect()
+    }
+
+    /// Parse the text format produced by `to_text`.
+    ///
+    /// Each line must contain a word and a roman digit separated by whitespace.
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn from_text(text: &'a str) -> Result<Self, QueryError> {
+        let mut lang = Self::new();
+
+        for line in text.lines() {
+            if let Some((word, digit)) = Self::parse_line(line)? {
+                lang.insert(word, digit);
+            }
+        }
+
+        Ok(lang)
+    }
+
+    /// Parse the text format produced by `to_text` line by line, without reading it all at once.
+    ///
+    /// Malformed lines are `io::ErrorKind::InvalidData` errors wrapping a `QueryError`.
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut lang = Self::new();
+
+        for line in reader.lines() {
+            let line = line?;
+            let pair = Self::parse_line(&line)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            if let Some((word, digit)) = pair {
+                lang.insert(word.to_string(), digit);
+            }
+        }
+
+        Ok(lang)
+    }
+
+    /// Write the text format produced by `to_text`, one line at a time.
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for (word, digit) in self.to_sorted_vec() {
+            writeln!(writer, "{word} {digit}")?;
+        }
+        writer.flush()
+    }
+
+    /// Parses a line of the text format, or returns `None` for blank lines and comments.
+    fn parse_line(line: &str) -> Result<Option<(&str, char)>, QueryError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let mut fields = line.split_whitespace();
+        let (word, digit) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(word), Some(digit), None) => (word, digit),
+            _ => return Err(QueryError::MalformedLine(line.to_string())),
+        };
+
+        let mut chars = digit.chars();
+        match (chars.next(), chars.next()) {
+            (Some(digit), None) if Roman::digit_value(digit).is_some() => Ok(Some((word, digit))),
+            _ => Err(QueryError::MalformedLine(line.to_string())),
+        }
+    }
+}
+
+impl<'a> From<BTreeMap<Cow<'a, str>, char>> for Language<'a> {
+    fn from(map: BTreeMap<Cow<'a, str>, char>) -> Self {
+        Self::with(map.into_iter().collect())
+    }
+}
+
+impl<'a> Extend<(Cow<'a, str>, char)> for Language<'a> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Cow<'a, str>, char)>,
+    {
+        Language::extend(self, iter);
     }
 }
 
@@ -94,4 +1041,762 @@ mod tests {
         assert!(lang.translate("foo glob").is_err());
         assert!(lang.translate("glob glob glob glob").is_err());
     }
+
+    #[test]
+    fn max_tokens() {
+        let mut lang = Language::with(HashMap::from([(Cow::from("glob"), 'I')]));
+        assert_eq!(lang.max_tokens(), Language::DEFAULT_MAX_TOKENS);
+
+        let long = vec!["glob"; 1_000_000].join(" ");
+        assert!(matches!(
+            lang.translate(&long),
+            Err(QueryError::PhraseTooLong(1024))
+        ));
+
+        lang.set_max_tokens(2);
+        assert!(lang.translate("glob glob").is_ok());
+        assert!(matches!(
+            lang.translate("glob glob glob"),
+            Err(QueryError::PhraseTooLong(2))
+        ));
+    }
+
+    #[test]
+    fn cache() {
+        let mut lang = Language::with_cache(2);
+        lang.insert("glob", 'I');
+        lang.insert("pish", 'X');
+        assert_eq!(lang.cached_len(), 0);
+
+        // hits return the same translation
+        let first = lang.translate("pish glob glob").unwrap();
+        assert_eq!(lang.cached_len(), 1);
+        assert_eq!(lang.translate("pish glob glob").unwrap(), first);
+        assert_eq!(lang.cached_len(), 1);
+
+        // errors aren't cached
+        assert!(lang.translate("glob foo").is_err());
+        assert_eq!(lang.cached_len(), 1);
+
+        // the least recently used phrase is evicted
+        lang.translate("glob").unwrap();
+        lang.translate("pish glob glob").unwrap();
+        lang.translate("pish").unwrap();
+        assert_eq!(lang.cached_len(), 2);
+        assert!(lang.cache.as_ref().unwrap().get("glob").is_none());
+        assert!(lang.cache.as_ref().unwrap().get("pish glob glob").is_some());
+
+        // a hit makes a phrase the most recently used
+        lang.translate("glob glob").unwrap();
+        assert!(lang.cache.as_ref().unwrap().get("pish").is_none());
+        assert!(lang.cache.as_ref().unwrap().get("pish glob glob").is_some());
+        for phrase in ["glob", "pish", "glob glob", "pish pish", "glob"] {
+            lang.translate(phrase).unwrap();
+        }
+        assert_eq!(lang.cached_len(), 2);
+        assert!(lang.cache.as_ref().unwrap().get("pish pish").is_some());
+        assert!(lang.cache.as_ref().unwrap().get("glob").is_some());
+
+        // changing the words invalidates the cache
+        lang.insert("glob", 'V');
+        assert_eq!(lang.cached_len(), 0);
+        assert!(lang.translate("pish glob glob").is_err());
+        assert_eq!(lang.translate("pish glob").unwrap(), "XV");
+
+        // clones and uncached languages compare equal
+        let mut uncached = Language::new();
+        uncached.insert("glob", 'V');
+        uncached.insert("pish", 'X');
+        assert_eq!(lang, uncached);
+        assert_eq!(lang.clone().cached_len(), 0);
+    }
+
+    #[test]
+    fn equality() {
+        let mut lang = Language::new();
+        lang.insert("glob", 'I');
+        lang.insert("pish", 'X');
+
+        let mut other = Language::new();
+        other.insert(String::from("pish"), 'X');
+        other.insert("glob", 'I');
+        assert_eq!(lang, other);
+
+        other.insert("glob", 'V');
+        assert_ne!(lang, other);
+    }
+
+    #[test]
+    fn subtractive_rules() {
+        let mut lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // classic rules by default
+        assert!(lang.translate("glob tegj").is_err());
+
+        let mut rules = SubtractiveRules::default();
+        rules.allow('I', 'L');
+        lang.set_subtractive_rules(rules);
+        assert_eq!(u32::from(lang.translate("glob tegj").unwrap()), 49);
+        assert_eq!(u32::from(lang.translate_ref("glob tegj").unwrap()), 49);
+        assert!(lang.translate("glob tegj glob").is_err());
+    }
+
+    #[test]
+    fn translate_is_canonical() {
+        let mut lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        assert_eq!(lang.translate_ref("pish tegj glob glob").unwrap(), "XLII");
+
+        let mut rules = SubtractiveRules::default();
+        rules.allow('I', 'L');
+        lang.set_subtractive_rules(rules);
+        assert_eq!(lang.translate_ref("glob tegj").unwrap(), "XLIX");
+        assert_eq!(lang.translate_ref("pish tegj glob").unwrap(), "XLI");
+
+        // negative tests
+        assert!(matches!(
+            lang.translate_ref("glob foo"),
+            Err(TranslationError::UnrecognizedWord("foo"))
+        ));
+        assert!(matches!(
+            lang.translate_ref("glob glob glob glob"),
+            Err(TranslationError::InvalidRomanNumeral(_))
+        ));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Language<'static>>();
+    }
+
+    #[test]
+    fn is_valid_phrase() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        assert!(lang.is_valid_phrase("glob"));
+        assert!(lang.is_valid_phrase("pish tegj glob glob"));
+
+        // negative tests
+        assert!(!lang.is_valid_phrase("glob foo"));
+        assert!(!lang.is_valid_phrase("glob glob glob glob"));
+        assert!(!lang.is_valid_phrase("glob tegj"));
+    }
+
+    #[test]
+    fn translate_inline() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        assert_eq!(
+            lang.translate_inline("I bought glob glob apples and pish tegj glob glob pears."),
+            "I bought 2 apples and 42 pears."
+        );
+        assert_eq!(lang.translate_inline("glob, prok,pish\ttegj!"), "1, 5,40!");
+        assert_eq!(lang.translate_inline("globglob tegj"), "globglob 50");
+
+        // invalid numerals and unknown words are left untouched
+        assert_eq!(
+            lang.translate_inline("glob glob glob glob and foo"),
+            "glob glob glob glob and foo"
+        );
+        assert_eq!(lang.translate_inline(""), "");
+    }
+
+    #[test]
+    fn inline_translator() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        let text = "glob glob apples, pish\ttegj pears and glob glob glob glob plums for prok";
+        let segments = InlineTranslator::new(&lang, text).collect::<Vec<_>>();
+        assert_eq!(
+            segments,
+            [
+                Segment::Numeral {
+                    text: "glob glob",
+                    value: Quantity(2)
+                },
+                Segment::Text(" apples, "),
+                Segment::Numeral {
+                    text: "pish\ttegj",
+                    value: Quantity(40)
+                },
+                Segment::Text(" pears and glob glob glob glob plums for "),
+                Segment::Numeral {
+                    text: "prok",
+                    value: Quantity(5)
+                },
+            ]
+        );
+        assert_eq!(
+            segments.iter().map(ToString::to_string).collect::<String>(),
+            lang.translate_inline(text)
+        );
+
+        // lazy, numerals are only looked for as far as needed
+        let mut segments = InlineTranslator::new(&lang, "foo glob bar");
+        assert_eq!(segments.next(), Some(Segment::Text("foo ")));
+        assert_eq!(segments.text[segments.scan..], *" bar");
+
+        // no numerals
+        assert_eq!(
+            InlineTranslator::new(&lang, "no numerals").collect::<Vec<_>>(),
+            [Segment::Text("no numerals")]
+        );
+        assert_eq!(InlineTranslator::new(&lang, "").next(), None);
+    }
+
+    #[test]
+    fn translate_concatenated() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // unambiguous
+        assert_eq!(lang.translate_concatenated("globglob").unwrap(), "II");
+        assert_eq!(
+            lang.translate_concatenated("pishtegjglobglob").unwrap(),
+            "XLII"
+        );
+        assert_eq!(lang.translate_concatenated("prok").unwrap(), "V");
+
+        // ambiguous, the longest word that leaves a valid split wins
+        let lang = Language::with(HashMap::from([
+            (Cow::from("ka"), 'I'),
+            (Cow::from("kal"), 'X'),
+            (Cow::from("la"), 'V'),
+        ]));
+        assert_eq!(lang.translate_concatenated("kala").unwrap(), "IV");
+        assert_eq!(lang.translate_concatenated("kalka").unwrap(), "XI");
+
+        // negative tests
+        assert!(lang.translate_concatenated("").is_err());
+        assert!(lang.translate_concatenated("kafoo").is_err());
+        assert!(lang.translate_concatenated("kakakaka").is_err());
+    }
+
+    #[test]
+    fn is_ambiguous() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("ka"), 'I'),
+            (Cow::from("kal"), 'X'),
+            (Cow::from("la"), 'V'),
+            (Cow::from("l"), 'L'),
+        ]));
+
+        // unambiguous
+        assert!(!lang.is_ambiguous("kaka"));
+        assert!(!lang.is_ambiguous("lka"));
+        // unsplittable numerals aren't ambiguous either
+        assert!(!lang.is_ambiguous("kafoo"));
+        assert!(!lang.is_ambiguous(""));
+
+        // ambiguous, `kal la` or `ka l la`
+        assert!(lang.is_ambiguous("kalla"));
+        assert!(lang.is_ambiguous("kalkal"));
+    }
+
+    #[test]
+    fn query() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+        ]));
+        let borrowed = HashMap::from([("Gold", Decimal::from(10))]);
+        let cow = HashMap::from([(Cow::from("Gold"), Decimal::from(10))]);
+
+        // positive tests
+        assert_eq!(
+            lang.query("glob prok", "Gold", &borrowed).unwrap(),
+            Decimal::from(40)
+        );
+        assert_eq!(
+            lang.query("glob prok", "Gold", &cow).unwrap(),
+            Decimal::from(40)
+        );
+
+        // negative tests
+        assert!(matches!(
+            lang.query("glob", "Iron", &cow),
+            Err(QueryError::UnrecognizedItem(_))
+        ));
+        assert!(matches!(
+            lang.query("foo", "Gold", &borrowed),
+            Err(QueryError::UnrecognizedWord(_))
+        ));
+    }
+
+    #[test]
+    fn explain() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        let explanation = lang.explain("pish tegj glob glob").unwrap();
+        assert_eq!(
+            explanation.words,
+            vec![("pish", 'X'), ("tegj", 'L'), ("glob", 'I'), ("glob", 'I')]
+        );
+        assert_eq!(explanation.roman, "XLII");
+        assert_eq!(explanation.value(), 42);
+        assert_eq!(
+            explanation.to_string(),
+            "pish -> X\ntegj -> L\nglob -> I\nglob -> I\nXLII = 42"
+        );
+
+        // negative tests
+        assert!(matches!(
+            lang.explain("pish foo"),
+            Err(TranslationError::UnrecognizedWord("foo"))
+        ));
+        assert!(matches!(
+            lang.explain("glob glob glob glob"),
+            Err(TranslationError::InvalidRomanNumeral(_))
+        ));
+    }
+
+    #[test]
+    fn translate_ref() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        for text in ["glob", "glob glob glob", "pish tegj glob glob"] {
+            assert_eq!(
+                lang.translate_ref(text).unwrap(),
+                lang.translate(text).unwrap()
+            );
+        }
+
+        // negative tests
+        assert!(matches!(
+            lang.translate_ref("glob foo"),
+            Err(TranslationError::UnrecognizedWord("foo"))
+        ));
+        assert!(matches!(
+            lang.translate_ref("glob glob glob glob"),
+            Err(TranslationError::InvalidRomanNumeral(_))
+        ));
+        let long = vec!["glob"; 20].join(" ") + " foo";
+        assert!(matches!(
+            lang.translate_ref(&long),
+            Err(TranslationError::UnrecognizedWord("foo"))
+        ));
+        assert!(lang.translate_ref("").is_err());
+    }
+
+    #[test]
+    fn to_intergalactic() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("diez"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        let roman = Roman::try_from(42u32).unwrap();
+        assert_eq!(
+            lang.to_intergalactic(&roman).unwrap(),
+            "diez tegj glob glob"
+        );
+        let roman = Roman::try_from("XIV").unwrap();
+        assert_eq!(
+            lang.translate(&lang.to_intergalactic(&roman).unwrap())
+                .unwrap(),
+            roman
+        );
+
+        // negative tests
+        let roman = Roman::try_from(100u32).unwrap();
+        assert!(matches!(
+            lang.to_intergalactic(&roman),
+            Err(QueryError::MissingDigitWord('C'))
+        ));
+    }
+
+    #[test]
+    fn translate_between() {
+        let a = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+        let b = Language::with(HashMap::from([
+            (Cow::from("uno"), 'I'),
+            (Cow::from("cinco"), 'V'),
+            (Cow::from("diez"), 'X'),
+        ]));
+
+        // positive tests
+        assert_eq!(
+            super::translate_between(&a, &b, "pish prok glob glob").unwrap(),
+            "diez cinco uno uno"
+        );
+        assert_eq!(
+            super::translate_between(&b, &a, "diez uno cinco").unwrap(),
+            "pish glob prok"
+        );
+
+        // negative tests
+        assert!(matches!(
+            super::translate_between(&a, &b, "pish tegj"),
+            Err(TranslationError::MissingDigitWord('L'))
+        ));
+        assert!(matches!(
+            super::translate_between(&a, &b, "glob foo"),
+            Err(TranslationError::UnrecognizedWord("foo"))
+        ));
+        assert!(matches!(
+            super::translate_between(&a, &b, "glob glob glob glob"),
+            Err(TranslationError::InvalidRomanNumeral(_))
+        ));
+    }
+
+    #[test]
+    fn levenshtein() {
+        assert_eq!(super::levenshtein("glob", "glob"), 0);
+        assert_eq!(super::levenshtein("glob", "blob"), 1);
+        assert_eq!(super::levenshtein("glob", "gob"), 1);
+        assert_eq!(super::levenshtein("kitten", "sitting"), 3);
+        assert_eq!(super::levenshtein("", "pish"), 4);
+    }
+
+    #[test]
+    fn closest_word() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // near misses
+        assert_eq!(lang.closest_word("glbo"), Some("glob"));
+        assert_eq!(lang.closest_word("prk"), Some("prok"));
+        assert_eq!(lang.closest_word("tegjj"), Some("tegj"));
+        assert_eq!(lang.closest_word("pish"), Some("pish"));
+
+        // far misses
+        assert_eq!(lang.closest_word("silver"), None);
+        assert_eq!(lang.closest_word("x"), None);
+        assert_eq!(Language::new().closest_word("glob"), None);
+    }
+
+    #[test]
+    fn words_with_prefix() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("pishy"), 'L'),
+        ]));
+
+        // positive tests
+        assert_eq!(lang.words_with_prefix("p"), ["pish", "pishy", "prok"]);
+        assert_eq!(lang.words_with_prefix("pish"), ["pish", "pishy"]);
+        assert_eq!(lang.words_with_prefix("").len(), 4);
+
+        // negative tests
+        assert!(lang.words_with_prefix("Glob").is_empty());
+        assert!(lang.words_with_prefix("tegj").is_empty());
+    }
+
+    #[test]
+    fn translate_word() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        for word in ["glob", "prok", "pish", "tegj"] {
+            assert_eq!(
+                lang.translate_word(word).unwrap(),
+                lang.translate(word).unwrap()
+            );
+        }
+
+        // negative tests
+        assert!(lang.translate_word("foo").is_err());
+        assert!(lang.translate_word("glob glob").is_err());
+    }
+
+    #[test]
+    fn extend() {
+        let mut lang = Language::with(HashMap::from([(Cow::from("glob"), 'I')]));
+
+        lang.extend([
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+            (Cow::from("pish"), 'C'),
+        ]);
+
+        for word in ["glob", "prok", "pish", "tegj"] {
+            assert!(lang.contains(word));
+        }
+        assert_eq!(
+            lang.translate("pish").unwrap(),
+            Roman::try_from("C").unwrap()
+        );
+
+        // trait impl
+        Extend::extend(&mut lang, [(Cow::from("kraz"), 'M')]);
+        assert!(lang.contains("kraz"));
+    }
+
+    #[test]
+    fn get_or_insert_with() {
+        let mut lang = Language::with(HashMap::from([(Cow::from("glob"), 'I')]));
+        let mut calls = 0;
+
+        // hit
+        let digit = lang.get_or_insert_with("glob", || {
+            calls += 1;
+            'X'
+        });
+        assert_eq!(digit, 'I');
+        assert_eq!(calls, 0);
+
+        // miss
+        let digit = lang.get_or_insert_with("pish", || {
+            calls += 1;
+            'X'
+        });
+        assert_eq!(digit, 'X');
+        assert_eq!(calls, 1);
+        assert_eq!(lang.get("pish"), Some('X'));
+    }
+
+    #[test]
+    fn define_digit() {
+        let mut lang = Language::new();
+
+        // positive tests
+        lang.define_digit('I', &["glob", "uno", "ein"]).unwrap();
+        lang.define_digit('X', &["pish", "diez"]).unwrap();
+        assert_eq!(lang.to_sorted_vec().len(), 5);
+        assert_eq!(
+            lang.translate("diez ein uno glob").unwrap(),
+            Roman::try_from("XIII").unwrap()
+        );
+
+        // negative tests
+        assert!(lang.define_digit('Q', &["foo"]).is_err());
+        assert!(!lang.contains("foo"));
+    }
+
+    #[test]
+    fn remap_digit() {
+        let mut lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("diez"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        // positive tests
+        assert_eq!(lang.remap_digit('X', 'C'), 2);
+        assert_eq!(lang.get("pish"), Some('C'));
+        assert_eq!(lang.get("diez"), Some('C'));
+        assert_eq!(lang.get("tegj"), Some('L'));
+        assert_eq!(lang.remap_digit('X', 'C'), 0);
+
+        // negative tests
+        assert_eq!(lang.remap_digit('L', 'Q'), 0);
+        assert_eq!(lang.get("tegj"), Some('L'));
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut lang = Language::new();
+        for i in 0..1000 {
+            lang.insert(format!("word{i}"), 'I');
+        }
+        for i in 4..1000 {
+            lang.remove(&format!("word{i}"));
+        }
+
+        lang.shrink_to_fit();
+
+        assert!(lang.map.capacity() < 1000);
+        for i in 0..4 {
+            assert!(lang.contains(&format!("word{i}")));
+        }
+        assert_eq!(lang.map.len(), 4);
+    }
+
+    #[test]
+    fn known_digits_sorted() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("blat"), 'M'),
+            (Cow::from("glob"), 'I'),
+            (Cow::from("uno"), 'I'),
+            (Cow::from("tegj"), 'L'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("kraz"), 'C'),
+            (Cow::from("foo"), 'Q'),
+        ]));
+        assert_eq!(lang.known_digits_sorted(), ['I', 'V', 'L', 'C', 'M', 'Q']);
+        assert!(Language::new().known_digits_sorted().is_empty());
+    }
+
+    #[test]
+    fn coverable_max() {
+        let complete = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+            (Cow::from("kraz"), 'C'),
+            (Cow::from("shon"), 'D'),
+            (Cow::from("blat"), 'M'),
+        ]));
+        assert_eq!(complete.coverable_max(), 3999);
+
+        let partial = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+        assert_eq!(partial.coverable_max(), 89);
+
+        let gaps = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("pish"), 'X'),
+        ]));
+        assert_eq!(gaps.coverable_max(), 39);
+
+        assert_eq!(Language::new().coverable_max(), 0);
+    }
+
+    #[test]
+    fn sorted() {
+        let lang = Language::from(BTreeMap::from([
+            (Cow::from("tegj"), 'L'),
+            (Cow::from("glob"), 'I'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("prok"), 'V'),
+        ]));
+
+        assert_eq!(
+            lang.to_sorted_vec(),
+            vec![("glob", 'I'), ("pish", 'X'), ("prok", 'V'), ("tegj", 'L')]
+        );
+        assert!(Language::new().to_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn fingerprint() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+        ]));
+        let mut same = Language::new();
+        same.insert("prok", 'V');
+        same.insert("glob", 'I');
+        assert_eq!(lang.fingerprint(), same.fingerprint());
+
+        let mut changed = same.clone();
+        changed.insert("pish", 'X');
+        assert_ne!(lang.fingerprint(), changed.fingerprint());
+
+        let swapped = Language::with(HashMap::from([
+            (Cow::from("glob"), 'V'),
+            (Cow::from("prok"), 'I'),
+        ]));
+        assert_ne!(lang.fingerprint(), swapped.fingerprint());
+        assert_ne!(lang.fingerprint(), Language::new().fingerprint());
+    }
+
+    #[test]
+    fn text() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        let text = lang.to_text();
+        assert_eq!(text, "glob I\npish X\nprok V\ntegj L\n");
+
+        let parsed = Language::from_text(&text).unwrap();
+        assert_eq!(parsed.map, lang.map);
+
+        let parsed = Language::from_text("# comment\n\n  glob   I\n").unwrap();
+        assert!(parsed.contains("glob"));
+
+        // negative tests
+        assert!(Language::from_text("glob").is_err());
+        assert!(Language::from_text("glob I V").is_err());
+        assert!(Language::from_text("glob Q").is_err());
+        assert!(Language::from_text("glob II").is_err());
+    }
+
+    #[test]
+    fn reader_writer() {
+        let lang = Language::with(HashMap::from([
+            (Cow::from("glob"), 'I'),
+            (Cow::from("prok"), 'V'),
+            (Cow::from("pish"), 'X'),
+            (Cow::from("tegj"), 'L'),
+        ]));
+
+        let mut buffer = Vec::new();
+        lang.to_writer(&mut buffer).unwrap();
+        assert_eq!(buffer, lang.to_text().as_bytes());
+
+        let parsed = Language::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(parsed, lang);
+
+        let parsed = Language::from_reader("# comment\n\n  glob   I\r\n".as_bytes()).unwrap();
+        assert_eq!(parsed.get("glob"), Some('I'));
+
+        // negative tests
+        let err = Language::from_reader("glob I\nprok\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("prok"));
+        assert!(Language::from_reader(&[0xff, b' ', b'I'][..]).is_err());
+    }
 }
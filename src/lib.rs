@@ -30,9 +30,16 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "apostrophus")]
+pub mod apostrophus;
 pub mod assistant;
+pub mod credits;
 pub mod error;
 pub mod language;
+pub mod numeral;
+pub mod quantity;
 pub mod roman;
 
-pub use assistant::Ford;
+pub use assistant::{Fact, Ford, PriceFormat, QueryKind, Rounding};
+pub use credits::Credits;
+pub use quantity::Quantity;
@@ -1,12 +1,16 @@
+use std::env;
 use std::error::Error as StdError;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
 
-use clap::Parser;
+use clap::{CommandFactory, ErrorKind, Parser, Subcommand};
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
+use intra::error::QueryError;
 use intra::Ford;
 
 const ERROR_STR: &str = "I have no idea what you are talking about";
@@ -14,47 +18,310 @@ const ERROR_STR: &str = "I have no idea what you are talking about";
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// Script to run before anything else, e.g. word and item definitions. Answers are discarded.
+    #[clap(long, value_parser, global = true)]
+    load: Option<PathBuf>,
+
     /// File to write to. Defaults to stdout. Ignored if in interactive mode.
     #[clap(short, long, value_parser)]
     output: Option<PathBuf>,
 
-    /// File to read from. Defaults to stdin.
+    /// Files to read from, in order and sharing definitions. Defaults to stdin.
     #[clap(value_parser)]
-    path: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+
+    /// File to keep the interactive history in. Defaults to ~/.intra_history.
+    #[clap(long, value_parser, global = true)]
+    history: Option<PathBuf>,
+
+    /// Don't load or save the interactive history.
+    #[clap(long, global = true)]
+    no_history: bool,
+
+    /// Report rejected lines on stderr with their line number and exit with an error.
+    /// Ignored if in interactive mode.
+    #[clap(long)]
+    strict: bool,
+
+    /// Check that every line of the input files is a known query and that definitions don't
+    /// conflict, without answering questions. Problems are reported on stderr with their line
+    /// number, and exit with an error if there are any.
+    #[clap(long, requires = "paths")]
+    validate: bool,
+
+    /// Acknowledge every definition with a line such as "OK: glob = I", instead of staying silent.
+    #[clap(long, global = true)]
+    echo_acks: bool,
+
+    /// Answer queries over TCP on this address instead, e.g. 127.0.0.1:4000.
+    /// Input files can't be given, use --load to run a script first.
+    #[clap(long, value_parser, conflicts_with = "paths")]
+    listen: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Translate an intergalactic numeral to a decimal number.
+    /// The words must be defined first with --load.
+    Translate {
+        /// The numeral, e.g. "pish tegj glob glob".
+        phrase: String,
+    },
+    /// Answer a single query, e.g. "How many credits is glob prok Gold?".
+    /// The words and prices must be defined first with --load.
+    Price {
+        /// The query.
+        query: String,
+    },
+    /// Run in interactive mode. This is the default without an input file.
+    Repl,
+}
+
+impl Args {
+    /// Exits with a usage error if an option that only applies without a subcommand is given
+    /// together with one, instead of ignoring it.
+    fn check_subcommand_conflicts(&self) {
+        let command = match &self.command {
+            Some(Command::Translate { .. }) => "translate",
+            Some(Command::Price { .. }) => "price",
+            Some(Command::Repl) => "repl",
+            None => return,
+        };
+
+        let conflicts = [
+            ("<PATHS>...", !self.paths.is_empty()),
+            ("--output", self.output.is_some()),
+            ("--strict", self.strict),
+            ("--validate", self.validate),
+            ("--listen", self.listen.is_some()),
+        ];
+        if let Some((arg, _)) = conflicts.into_iter().find(|(_, given)| *given) {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("The argument '{arg}' cannot be used with the subcommand '{command}'"),
+                )
+                .exit();
+        }
+    }
+
+    fn history_path(&self) -> Option<PathBuf> {
+        if self.no_history {
+            return None;
+        }
+
+        self.history.clone().or_else(|| {
+            env::var_os("HOME")
+                .or_else(|| env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".intra_history"))
+        })
+    }
+}
+
+/// Answers the queries of a single client until it disconnects.
+fn serve(ford: &mut Ford, stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let result = ford
+            .query(&line?)
+            .unwrap_or_else(|_| Some(ERROR_STR.to_string()));
+
+        if let Some(line) = result {
+            writeln!(writer, "{line}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens a file for reading, naming it in the error if it can't be opened.
+fn open(path: &Path) -> io::Result<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {err}", path.display())))
+}
+
+/// Runs every line of a script through `ford`, reporting rejected lines on stderr.
+fn load(ford: &mut Ford, path: &Path) -> io::Result<()> {
+    let file = open(path)?;
+
+    for (number, line) in file.lines().enumerate() {
+        if let Err(err) = ford.query(&line?) {
+            eprintln!("{}:{}: {err}", path.display(), number + 1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the prefix naming a file in reports of its lines.
+///
+/// Lines only need their file named if there's more than one.
+fn location(paths: &[PathBuf], path: &Path) -> String {
+    if paths.len() > 1 {
+        format!("{}: ", path.display())
+    } else {
+        String::new()
+    }
+}
+
+/// Checks every line of a script, reporting problems on stderr. Returns the number of problems.
+///
+/// Definitions are run through `ford`, so later lines are checked against them,
+/// but questions are only classified and never answered.
+fn validate(ford: &mut Ford, path: &Path, location: &str) -> io::Result<usize> {
+    let file = open(path)?;
+    let mut problems = 0;
+
+    for (number, line) in file.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = match Ford::classify(&line) {
+            Some(kind) if kind.is_definition() => ford.query(&line).map(|_| ()),
+            Some(_) => Ok(()),
+            None => Err(QueryError::UnrecognizedQuery(line.clone())),
+        };
+
+        if let Err(err) = result {
+            eprintln!("{location}line {}: {err}", number + 1);
+            problems += 1;
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Prints the answer of a subcommand, or exits with an error.
+fn answer(result: Result<Option<String>, QueryError>) {
+    match result {
+        Ok(Some(answer)) => println!("{answer}"),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
 }
 
 fn main() -> Result<(), Box<dyn StdError>> {
     let args = Args::parse();
+    args.check_subcommand_conflicts();
 
     let mut ford = Ford::new();
 
-    if let Some(path) = &args.path {
-        let file = BufReader::new(File::open(path)?);
+    if let Some(path) = &args.load {
+        load(&mut ford, path)?;
+    }
+    // definitions loaded from a script are never acknowledged
+    ford.set_verbose(args.echo_acks);
+
+    match &args.command {
+        Some(Command::Translate { phrase }) => {
+            let result = ford.language().translate(phrase);
+            answer(result.map(|roman| Some(u32::from(roman).to_string())));
+            return Ok(());
+        }
+        Some(Command::Price { query }) => {
+            answer(ford.query(query));
+            return Ok(());
+        }
+        Some(Command::Repl) | None => {}
+    }
+
+    if args.validate {
+        let mut problems = 0;
+
+        for path in &args.paths {
+            let location = location(&args.paths, path);
+            problems += validate(&mut ford, path, &location).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                process::exit(1);
+            });
+        }
+
+        if problems > 0 {
+            eprintln!("{problems} problem(s) found");
+            process::exit(1);
+        }
 
-        let mut out_file: Box<dyn Write> = if let Some(output) = args.output {
+        return Ok(());
+    }
+
+    if let Some(addr) = &args.listen {
+        let listener = TcpListener::bind(addr)?;
+        eprintln!("Listening on {}", listener.local_addr()?);
+
+        for stream in listener.incoming() {
+            // a client going away only ends its own session
+            if let Err(err) = stream.and_then(|stream| serve(&mut ford, stream)) {
+                eprintln!("Connection error: {err}");
+            }
+        }
+
+        Ok(())
+    } else if !args.paths.is_empty() {
+        let mut out_file: Box<dyn Write> = if let Some(output) = &args.output {
             Box::new(File::create(output)?)
         } else {
             Box::new(io::stdout())
         };
 
-        for line in file.lines() {
-            let line = line?;
-            let result = ford
-                .query(&line)
-                .unwrap_or_else(|_| Some(ERROR_STR.to_string()));
+        let mut rejected = 0;
+
+        for path in &args.paths {
+            let file = open(path).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                process::exit(1);
+            });
+            let location = location(&args.paths, path);
+
+            for (number, line) in file.lines().enumerate() {
+                let line = line?;
+                let result = match ford.query(&line) {
+                    Ok(result) => result,
+                    Err(err) if args.strict => {
+                        eprintln!("{location}line {}: {err}", number + 1);
+                        rejected += 1;
+                        None
+                    }
+                    Err(_) => Some(ERROR_STR.to_string()),
+                };
 
-            if let Some(line) = result {
-                writeln!(out_file, "{line}")?;
+                if let Some(line) = result {
+                    writeln!(out_file, "{line}")?;
+                }
             }
         }
 
+        if rejected > 0 {
+            out_file.flush()?;
+            eprintln!("{rejected} line(s) rejected");
+            process::exit(1);
+        }
+
         Ok(())
     } else {
+        let history = args.history_path();
+
         let mut rl = Editor::<()>::new();
+        if let Some(history) = &history {
+            // a missing or unreadable history just starts a fresh one
+            let _ = rl.load_history(history);
+        }
+
         loop {
             let readline = rl.readline("> ");
             match readline {
                 Ok(line) => {
+                    rl.add_history_entry(line.as_str());
+
                     let result = ford
                         .query(&line)
                         .unwrap_or_else(|_| Some(ERROR_STR.to_string()));
@@ -78,6 +345,12 @@ fn main() -> Result<(), Box<dyn StdError>> {
             }
         }
 
+        if let Some(history) = &history {
+            if let Err(err) = rl.save_history(history) {
+                eprintln!("Could not save history to {}: {err}", history.display());
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,70 @@
+//! An abstraction over numeral systems, so code can work with numerals other than `Roman`.
+use crate::roman::Roman;
+
+/// `Numeral` is a written representation of positive integers.
+///
+/// # Examples
+/// ```
+/// use intra::numeral::Numeral;
+/// use intra::roman::Roman;
+///
+/// fn double<N: Numeral>(text: &str) -> Option<N> {
+///     N::try_from_u32(N::try_from_str(text)?.to_u32() * 2)
+/// }
+///
+/// assert_eq!(double::<Roman>("XXI").unwrap(), "XLII");
+/// ```
+pub trait Numeral: Sized {
+    /// Returns the value of the numeral.
+    fn to_u32(&self) -> u32;
+
+    /// Encodes a value, or returns `None` if it can't be represented.
+    fn try_from_u32(value: u32) -> Option<Self>;
+
+    /// Parses a numeral, or returns `None` if the text isn't valid.
+    fn try_from_str(text: &str) -> Option<Self>;
+
+    /// Checks if a text is a valid numeral.
+    fn is_valid(text: &str) -> bool {
+        Self::try_from_str(text).is_some()
+    }
+}
+
+impl Numeral for Roman {
+    fn to_u32(&self) -> u32 {
+        u32::from(self)
+    }
+
+    fn try_from_u32(value: u32) -> Option<Self> {
+        Roman::try_from(value).ok()
+    }
+
+    fn try_from_str(text: &str) -> Option<Self> {
+        Roman::try_from(text).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<N: Numeral>(value: u32) -> Option<u32> {
+        N::try_from_u32(value).map(|numeral| numeral.to_u32())
+    }
+
+    #[test]
+    fn roman() {
+        // positive tests
+        assert_eq!(roundtrip::<Roman>(1), Some(1));
+        assert_eq!(roundtrip::<Roman>(42), Some(42));
+        assert_eq!(roundtrip::<Roman>(3999), Some(3999));
+        assert!(<Roman as Numeral>::is_valid("XLII"));
+        assert_eq!(Roman::try_from_str("XLII").unwrap().to_u32(), 42);
+
+        // negative tests
+        assert_eq!(roundtrip::<Roman>(0), None);
+        assert_eq!(roundtrip::<Roman>(4000), None);
+        assert!(!<Roman as Numeral>::is_valid("IIII"));
+        assert!(Roman::try_from_str("").is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! A typed count of items, as translated from a numeral.
+use std::fmt;
+use std::ops::Mul;
+
+use rust_decimal::Decimal;
+
+use crate::credits::Credits;
+use crate::error::InvalidRomanNumeral;
+use crate::roman::Roman;
+
+/// `Quantity` is a count of items, such as the value of a translated numeral.
+///
+/// `Quantity` displays as the plain number. It converts from a `Roman` and from and into a `u32`.
+/// A quantity multiplied by `Credits` is the total price in `Credits`,
+/// but two quantities can't be multiplied with each other.
+///
+/// # Examples
+/// ```
+/// use rust_decimal_macros::dec;
+/// use intra::roman::Roman;
+/// use intra::{Credits, Quantity};
+///
+/// let count = Quantity::from(Roman::try_from("XLII").unwrap());
+/// assert_eq!(count.to_string(), "42");
+/// assert_eq!(count * Credits(dec!(2.5)), Credits(dec!(105)));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quantity(pub u32);
+
+impl Quantity {
+    /// Returns the count.
+    pub fn count(self) -> u32 {
+        self.0
+    }
+
+    /// Adds quantities, returning `None` on overflow.
+    pub fn checked_add(self, other: Quantity) -> Option<Quantity> {
+        self.0.checked_add(other.0).map(Quantity)
+    }
+
+    /// Computes the total price of this many units at `unit_price` each,
+    /// returning `None` on overflow.
+    pub fn checked_mul(self, unit_price: Credits) -> Option<Credits> {
+        unit_price.checked_mul(Decimal::from(self))
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u32> for Quantity {
+    fn from(count: u32) -> Self {
+        Self(count)
+    }
+}
+
+impl From<Roman> for Quantity {
+    fn from(roman: Roman) -> Self {
+        Self(u32::from(roman))
+    }
+}
+
+impl<'r> From<&'r Roman> for Quantity {
+    fn from(roman: &'r Roman) -> Self {
+        Self(u32::from(roman))
+    }
+}
+
+impl TryFrom<Quantity> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(quantity: Quantity) -> Result<Self, InvalidRomanNumeral> {
+        Roman::try_from(quantity.0)
+    }
+}
+
+impl From<Quantity> for u32 {
+    fn from(quantity: Quantity) -> Self {
+        quantity.0
+    }
+}
+
+impl From<Quantity> for Decimal {
+    fn from(quantity: Quantity) -> Self {
+        Decimal::from(quantity.0)
+    }
+}
+
+impl PartialEq<u32> for Quantity {
+    fn eq(&self, other: &u32) -> bool {
+        self.0 == *other
+    }
+}
+
+/// The total price of a quantity at a unit price.
+impl Mul<Credits> for Quantity {
+    type Output = Credits;
+
+    fn mul(self, unit_price: Credits) -> Credits {
+        unit_price * Decimal::from(self)
+    }
+}
+
+/// The total price of a quantity at a unit price.
+impl Mul<Quantity> for Credits {
+    type Output = Credits;
+
+    fn mul(self, quantity: Quantity) -> Credits {
+        quantity * self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let roman = Roman::try_from("MCMXCIX").unwrap();
+
+        // positive tests
+        assert_eq!(Quantity::from(&roman), Quantity(1999));
+        assert_eq!(Quantity::from(roman.clone()), 1999);
+        assert_eq!(Roman::try_from(Quantity(1999)).unwrap(), roman);
+        assert_eq!(u32::from(Quantity::from(7)), 7);
+        assert_eq!(Decimal::from(Quantity(7)), dec!(7));
+        assert_eq!(Quantity(42).count(), 42);
+        assert_eq!(Quantity(42).to_string(), "42");
+        assert_eq!(Quantity(40).checked_add(Quantity(2)), Some(Quantity(42)));
+
+        // negative tests
+        assert!(Roman::try_from(Quantity(0)).is_err());
+        assert!(Roman::try_from(Quantity(4000)).is_err());
+        assert!(Quantity(u32::MAX).checked_add(Quantity(1)).is_none());
+    }
+
+    #[test]
+    fn price() {
+        let unit_price = Credits(dec!(19.5));
+
+        // positive tests
+        assert_eq!(Quantity(4) * unit_price, Credits(dec!(78)));
+        assert_eq!(unit_price * Quantity(4), Credits(dec!(78)));
+        assert_eq!(Quantity(0) * unit_price, Credits::ZERO);
+        assert_eq!(
+            Quantity(3).checked_mul(unit_price),
+            Some(Credits(dec!(58.5)))
+        );
+
+        // negative tests
+        assert!(Quantity(2).checked_mul(Credits(Decimal::MAX)).is_none());
+    }
+}
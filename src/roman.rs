@@ -1,4 +1,9 @@
 //! A representation for standard roman numerals. Digits go up to M.
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::num::NonZeroU32;
+use std::ops::Range;
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -10,12 +15,139 @@ lazy_static! {
         Regex::new("^M{0,3}(C[MD]|D?C{0,3})(X[CL]|L?X{0,3})(I[XV]|V?I{0,3})$").unwrap();
 }
 
+/// Which subtractive pairs, such as `IV`, are legal in a numeral.
+///
+/// The default allows the classic pairs `IV`, `IX`, `XL`, `XC`, `CD` and `CM`.
+///
+/// # Examples
+/// ```
+/// use intra::roman::{Roman, SubtractiveRules};
+/// let mut rules = SubtractiveRules::default();
+/// rules.allow('I', 'L');
+/// let roman = Roman::parse_with("IL", &rules).unwrap();
+/// assert_eq!(u32::from(roman), 49);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtractiveRules {
+    pairs: BTreeSet<(char, char)>,
+}
+
+impl Default for SubtractiveRules {
+    fn default() -> Self {
+        Self::with_pairs([
+            ('I', 'V'),
+            ('I', 'X'),
+            ('X', 'L'),
+            ('X', 'C'),
+            ('C', 'D'),
+            ('C', 'M'),
+        ])
+    }
+}
+
+impl SubtractiveRules {
+    /// Allow exactly the given pairs of a smaller digit followed by a larger one.
+    ///
+    /// Pairs that aren't a smaller roman digit followed by a larger one are ignored.
+    pub fn with_pairs<I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (char, char)>,
+    {
+        let mut rules = Self {
+            pairs: BTreeSet::new(),
+        };
+        for (smaller, larger) in pairs {
+            rules.allow(smaller, larger);
+        }
+        rules
+    }
+
+    /// Allow a smaller digit to be subtracted from a larger one.
+    ///
+    /// Returns `false` if the pair isn't a smaller roman digit followed by a larger one.
+    pub fn allow(&mut self, smaller: char, larger: char) -> bool {
+        match (Roman::digit_value(smaller), Roman::digit_value(larger)) {
+            (Some(a), Some(b)) if a < b => {
+                self.pairs.insert((smaller, larger));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if a smaller digit may be subtracted from a larger one.
+    pub fn is_allowed(&self, smaller: char, larger: char) -> bool {
+        self.pairs.contains(&(smaller, larger))
+    }
+
+    /// Computes the value of a numeral under these rules, or `None` if it isn't valid.
+    ///
+    /// The numeral is split into subtractive pairs and single digits, which must not increase.
+    /// Digits for powers of ten may be repeated up to three times, the others only once.
+    /// After a pair, everything must be smaller than the subtracted digit, so `XCX` is invalid.
+    fn value_of(&self, text: &str) -> Option<u32> {
+        // the largest digit the next token may contain, whether it must be strictly smaller
+        // because it follows a pair, and the last digit with its repetitions
+        let mut limit = u32::MAX;
+        let mut after_pair = false;
+        let mut run: Option<(u32, usize)> = None;
+        let mut total = 0;
+
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut index = 0;
+        while index < chars.len() {
+            let digit = Roman::digit_value(chars[index])?;
+            let next = chars.get(index + 1).copied();
+
+            match next.and_then(|next| Some((next, Roman::digit_value(next)?))) {
+                Some((next, larger)) if digit < larger && self.is_allowed(chars[index], next) => {
+                    // the larger digit may match a preceding run of tens, as in XIX
+                    let repeatable = run.is_none_or(|(last, _)| last != larger || is_ten(larger));
+                    if larger > limit || !repeatable {
+                        return None;
+                    }
+                    total += larger - digit;
+                    limit = digit;
+                    after_pair = true;
+                    run = None;
+                    index += 2;
+                }
+                _ => {
+                    if digit > limit || (after_pair && digit == limit) {
+                        return None;
+                    }
+                    let count = match run {
+                        Some((last, count)) if last == digit => count + 1,
+                        _ => 1,
+                    };
+                    if count > if is_ten(digit) { 3 } else { 1 } {
+                        return None;
+                    }
+                    total += digit;
+                    limit = digit;
+                    after_pair = false;
+                    run = Some((digit, count));
+                    index += 1;
+                }
+            }
+        }
+
+        Some(total)
+    }
+}
+
+/// Checks if a digit value is a power of ten.
+fn is_ten(value: u32) -> bool {
+    matches!(value, 1 | 10 | 100 | 1000)
+}
+
 /// `Roman` represents all valid roman numerals.
 ///
 /// A `Roman` can be constructed using the `TryFrom` trait,
-/// by providing an owned `String` or a slice `&str`.
-/// Invalid numerals cannot be constructed.
-/// `Roman` can be converted into a `u32` by calling `u32::from`.
+/// by providing an owned `String`, a slice `&str`, a sequence of `char`s
+/// or a `u32` between 1 and 3999.
+/// Leading and trailing ASCII whitespace is ignored. Invalid numerals cannot be constructed.
+/// `Roman` can be converted into a `u32` by calling `u32::from`, or into a `NonZeroU32`.
 ///
 /// # Examples
 /// ```
@@ -31,72 +163,152 @@ lazy_static! {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Roman {
-    value: String,
+    value: Cow<'static, str>,
 }
 
 impl Roman {
-    fn is_valid(value: &str) -> bool {
-        ROMAN_REGEX.is_match(value)
+    /// The numeral `I`, 1.
+    pub const I: Roman = Roman::from_static("I");
+    /// The numeral `V`, 5.
+    pub const V: Roman = Roman::from_static("V");
+    /// The numeral `X`, 10.
+    pub const X: Roman = Roman::from_static("X");
+    /// The numeral `L`, 50.
+    pub const L: Roman = Roman::from_static("L");
+    /// The numeral `C`, 100.
+    pub const C: Roman = Roman::from_static("C");
+    /// The numeral `D`, 500.
+    pub const D: Roman = Roman::from_static("D");
+    /// The numeral `M`, 1000.
+    pub const M: Roman = Roman::from_static("M");
+
+    /// Constructs a numeral known to be valid, for constants.
+    const fn from_static(value: &'static str) -> Roman {
+        Roman {
+            value: Cow::Borrowed(value),
+        }
     }
 
-    fn digit_value(digit: char) -> u32 {
+    /// Returns the single-digit numeral for a digit, without allocating.
+    pub(crate) fn from_digit(digit: char) -> Option<Roman> {
         match digit {
-            'I' => 1,
-            'V' => 5,
-            'X' => 10,
-            'L' => 50,
-            'C' => 100,
-            'D' => 500,
-            'M' => 1000,
-            _ => 0,
+            'I' => Some(Roman::I),
+            'V' => Some(Roman::V),
+            'X' => Some(Roman::X),
+            'L' => Some(Roman::L),
+            'C' => Some(Roman::C),
+            'D' => Some(Roman::D),
+            'M' => Some(Roman::M),
+            _ => None,
         }
     }
-}
 
-impl<'a> TryFrom<&'a str> for Roman {
-    type Error = InvalidRomanNumeral;
+    pub(crate) fn is_valid(value: &str) -> bool {
+        // cheap checks first, so arbitrarily long or foreign input never reaches the regex
+        !value.is_empty()
+            && value.len() <= Roman::MAX_LEN
+            && value.chars().all(|c| Roman::digit_value(c).is_some())
+            && ROMAN_REGEX.is_match(value)
+    }
 
-    fn try_from(value: &'a str) -> Result<Self, InvalidRomanNumeral> {
-        if value.is_empty() {
-            return Err(InvalidRomanNumeral);
+    /// The number of digits in the longest valid numeral, MMMDCCCLXXXVIII.
+    pub(crate) const MAX_LEN: usize = 15;
+
+    /// Parses a numeral, ignoring leading and trailing ASCII whitespace.
+    ///
+    /// Never panics, whatever the input. This is what `TryFrom<&str>` uses.
+    pub fn checked_from_str(value: &str) -> Result<Roman, InvalidRomanNumeral> {
+        let value = value.trim_matches(|c: char| c.is_ascii_whitespace());
+
+        if Roman::is_valid(value) {
+            Ok(Self {
+                value: Cow::Owned(value.to_string()),
+            })
+        } else {
+            Err(InvalidRomanNumeral)
+        }
+    }
+
+    /// Checks if a text is a valid numeral, returning the byte index and character where it first
+    /// goes wrong otherwise, e.g. for placing a caret in an input box.
+    ///
+    /// Every prefix of a valid numeral is valid, so the error is at the first character that
+    /// makes the text read so far invalid. Whitespace isn't ignored. The empty text is reported
+    /// as `(0, '\0')`.
+    ///
+    /// # Examples
+    /// ```
+    /// use intra::roman::Roman;
+    /// assert_eq!(Roman::validate_detailed("XLII"), Ok(()));
+    /// assert_eq!(Roman::validate_detailed("XLIIII"), Err((5, 'I')));
+    /// ```
+    pub fn validate_detailed(text: &str) -> Result<(), (usize, char)> {
+        if text.is_empty() {
+            return Err((0, '\0'));
         }
 
-        Roman::try_from(value.to_string())
+        match text
+            .char_indices()
+            .find(|&(index, c)| !Roman::is_valid(&text[..index + c.len_utf8()]))
+        {
+            Some(violation) => Err(violation),
+            None => Ok(()),
+        }
     }
-}
 
-impl TryFrom<String> for Roman {
-    type Error = InvalidRomanNumeral;
+    /// Parses a numeral under custom subtractive rules, ignoring surrounding ASCII whitespace.
+    ///
+    /// The numeral is converted to the classic numeral with the same value,
+    /// so with `IL` allowed, `IL` is parsed as `XLIX`.
+    pub fn parse_with(value: &str, rules: &SubtractiveRules) -> Result<Roman, InvalidRomanNumeral> {
+        if *rules == SubtractiveRules::default() {
+            return Roman::checked_from_str(value);
+        }
 
-    fn try_from(value: String) -> Result<Self, InvalidRomanNumeral> {
-        if value.is_empty() {
+        let value = value.trim_matches(|c: char| c.is_ascii_whitespace());
+        if value.is_empty() || value.len() > 4 * Roman::MAX_LEN {
             return Err(InvalidRomanNumeral);
         }
 
-        if Roman::is_valid(&value) {
-            Ok(Self { value })
-        } else {
-            Err(InvalidRomanNumeral)
-        }
+        let value = rules.value_of(value).ok_or(InvalidRomanNumeral)?;
+        Roman::try_from(value)
     }
-}
 
-impl From<Roman> for u32 {
-    fn from(roman: Roman) -> Self {
-        Self::from(&roman)
+    /// Returns the value of a single roman digit, or `None` if `digit` isn't one.
+    ///
+    /// # Examples
+    /// ```
+    /// use intra::roman::Roman;
+    /// assert_eq!(Roman::digit_value('X'), Some(10));
+    /// assert_eq!(Roman::digit_value('Q'), None);
+    /// ```
+    pub fn digit_value(digit: char) -> Option<u32> {
+        match digit {
+            'I' => Some(1),
+            'V' => Some(5),
+            'X' => Some(10),
+            'L' => Some(50),
+            'C' => Some(100),
+            'D' => Some(500),
+            'M' => Some(1000),
+            _ => None,
+        }
     }
-}
 
-impl<'a> From<&'a Roman> for u32 {
-    fn from(roman: &'a Roman) -> Self {
+    /// Converts to `u32`, failing on unknown digits instead of counting them as zero.
+    ///
+    /// A constructed `Roman` is always valid, so this only fails if that invariant is broken.
+    fn try_value(&self) -> Result<u32, InvalidRomanNumeral> {
+        let value = |digit| Roman::digit_value(digit).ok_or(InvalidRomanNumeral);
+
         let mut acc = 0;
 
         let mut previous = None;
 
-        for current_char in roman.value.chars() {
+        for current_char in self.value.chars() {
             if let Some(last_char) = previous {
-                let last_digit = Roman::digit_value(last_char);
-                let current_digit = Roman::digit_value(current_char);
+                let last_digit = value(last_char)?;
+                let current_digit = value(current_char)?;
                 if last_char == current_char {
                     acc += current_digit + last_digit;
                     previous = None;
@@ -110,16 +322,243 @@ impl<'a> From<&'a Roman> for u32 {
             } else {
                 match current_char {
                     'I' | 'X' | 'C' => previous = Some(current_char),
-                    _ => acc += Roman::digit_value(current_char),
+                    _ => acc += value(current_char)?,
                 }
             }
         }
 
         if let Some(last_char) = previous {
-            acc += Roman::digit_value(last_char);
+            acc += value(last_char)?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Finds every roman numeral in a text, together with its byte range.
+    ///
+    /// The text is scanned from left to right, always taking the longest valid numeral.
+    /// Numerals don't overlap, but may be adjacent: `IIII` is found as `III` and `I`.
+    /// Word boundaries aren't respected, so any uppercase roman digit may start a numeral.
+    pub fn parse_all(text: &str) -> Vec<(Roman, Range<usize>)> {
+        let bytes = text.as_bytes();
+        let mut numerals = Vec::new();
+        let mut start = 0;
+
+        while start < bytes.len() {
+            // roman digits are ASCII, so every index in the run is a char boundary
+            let run = bytes[start..]
+                .iter()
+                .take(Roman::MAX_LEN)
+                .take_while(|&&byte| Roman::digit_value(byte as char).is_some())
+                .count();
+
+            let longest = (1..=run)
+                .rev()
+                .find(|&len| Roman::is_valid(&text[start..start + len]));
+
+            if let Some(len) = longest {
+                let range = start..start + len;
+                let value = text[range.clone()].to_string();
+                numerals.push((
+                    Self {
+                        value: Cow::Owned(value),
+                    },
+                    range,
+                ));
+                start += len;
+            } else {
+                start += 1;
+            }
+        }
+
+        numerals
+    }
+
+    /// Returns every valid numeral from I to MMMCMXCIX, in ascending order.
+    ///
+    /// Numerals are encoded one at a time as the iterator advances.
+    pub fn all() -> impl Iterator<Item = Roman> {
+        (1..=3999u32).map(|value| Roman::try_from(value).expect("1 to 3999 are all encodable"))
+    }
+
+    /// Returns the number of symbols in the numeral.
+    pub fn digit_count(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    /// Returns the numeral in lowercase, e.g. `xlii`, as used for pagination.
+    ///
+    /// The same is available via the alternate `Display` flag, `{:#}`.
+    pub fn to_lowercase_string(&self) -> String {
+        self.value.to_ascii_lowercase()
+    }
+
+    /// Multiplies the numeral by `factor`.
+    ///
+    /// Returns `None` if the result can't be represented as a `Roman`.
+    pub fn scale(&self, factor: u32) -> Option<Roman> {
+        u32::from(self)
+            .checked_mul(factor)
+            .and_then(|value| Roman::try_from(value).ok())
+    }
+
+    /// Divides the numeral by `divisor`, rounding down.
+    ///
+    /// Returns `None` if `divisor` is zero or the result can't be represented as a `Roman`.
+    pub fn divide(&self, divisor: u32) -> Option<Roman> {
+        u32::from(self)
+            .checked_div(divisor)
+            .and_then(|value| Roman::try_from(value).ok())
+    }
+}
+
+impl fmt::Display for Roman {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.to_lowercase_string())
+        } else {
+            f.write_str(&self.value)
+        }
+    }
+}
+
+impl PartialEq<str> for Roman {
+    fn eq(&self, other: &str) -> bool {
+        self.value == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Roman {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.value == **other
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(value: &'a str) -> Result<Self, InvalidRomanNumeral> {
+        Roman::checked_from_str(value)
+    }
+}
+
+impl TryFrom<String> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(mut value: String) -> Result<Self, InvalidRomanNumeral> {
+        let trimmed = value.trim_matches(|c: char| c.is_ascii_whitespace());
+        if trimmed.len() != value.len() {
+            value = trimmed.to_string();
+        }
+
+        if Roman::is_valid(&value) {
+            Ok(Self {
+                value: Cow::Owned(value),
+            })
+        } else {
+            Err(InvalidRomanNumeral)
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [char]> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(value: &'a [char]) -> Result<Self, InvalidRomanNumeral> {
+        Roman::try_from(value.iter().collect::<String>())
+    }
+}
+
+impl TryFrom<Vec<char>> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(value: Vec<char>) -> Result<Self, InvalidRomanNumeral> {
+        Roman::try_from(value.as_slice())
+    }
+}
+
+impl TryFrom<u32> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(mut value: u32) -> Result<Self, InvalidRomanNumeral> {
+        const TABLE: [(u32, &str); 13] = [
+            (1000, "M"),
+            (900, "CM"),
+            (500, "D"),
+            (400, "CD"),
+            (100, "C"),
+            (90, "XC"),
+            (50, "L"),
+            (40, "XL"),
+            (10, "X"),
+            (9, "IX"),
+            (5, "V"),
+            (4, "IV"),
+            (1, "I"),
+        ];
+
+        if !(1..=3999).contains(&value) {
+            return Err(InvalidRomanNumeral);
+        }
+
+        let mut numeral = String::new();
+        for (digit_value, digits) in TABLE {
+            while value >= digit_value {
+                numeral.push_str(digits);
+                value -= digit_value;
+            }
         }
 
-        acc
+        Ok(Self {
+            value: Cow::Owned(numeral),
+        })
+    }
+}
+
+impl TryFrom<u64> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(value: u64) -> Result<Self, InvalidRomanNumeral> {
+        u32::try_from(value)
+            .map_err(|_| InvalidRomanNumeral)
+            .and_then(Roman::try_from)
+    }
+}
+
+impl TryFrom<i64> for Roman {
+    type Error = InvalidRomanNumeral;
+
+    fn try_from(value: i64) -> Result<Self, InvalidRomanNumeral> {
+        u32::try_from(value)
+            .map_err(|_| InvalidRomanNumeral)
+            .and_then(Roman::try_from)
+    }
+}
+
+impl From<Roman> for u32 {
+    fn from(roman: Roman) -> Self {
+        Self::from(&roman)
+    }
+}
+
+impl<'a> From<&'a Roman> for u32 {
+    fn from(roman: &'a Roman) -> Self {
+        roman
+            .try_value()
+            .expect("a `Roman` only contains valid digits")
+    }
+}
+
+impl From<Roman> for NonZeroU32 {
+    fn from(roman: Roman) -> Self {
+        Self::from(&roman)
+    }
+}
+
+/// Valid numerals are never empty, so their value is always at least 1.
+impl<'a> From<&'a Roman> for NonZeroU32 {
+    fn from(roman: &'a Roman) -> Self {
+        NonZeroU32::new(u32::from(roman)).expect("a `Roman` is always positive")
     }
 }
 
@@ -146,6 +585,130 @@ mod tests {
         assert!(Roman::try_from("XM").is_err());
     }
 
+    #[test]
+    fn whitespace() {
+        // positive tests
+        assert_eq!(Roman::try_from(" XIV").unwrap(), "XIV");
+        assert_eq!(Roman::try_from("XIV \n").unwrap(), "XIV");
+        assert_eq!(Roman::try_from(String::from("\t XIV ")).unwrap(), "XIV");
+
+        // negative tests
+        assert!(Roman::try_from("X IV").is_err());
+        assert!(Roman::try_from(String::from(" X\tIV ")).is_err());
+        assert!(Roman::try_from("   ").is_err());
+    }
+
+    #[test]
+    fn chars() {
+        // positive tests
+        assert_eq!(Roman::try_from(vec!['X', 'I', 'V']).unwrap(), "XIV");
+        assert_eq!(Roman::try_from(&['M', 'C', 'M'][..]).unwrap(), "MCM");
+
+        // negative tests
+        assert!(Roman::try_from(vec!['I', 'I', 'I', 'I']).is_err());
+        assert!(Roman::try_from(vec!['X', 'Q']).is_err());
+        assert!(Roman::try_from(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn checked_from_str() {
+        // xorshift, so the test is reproducible without extra dependencies
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..10_000 {
+            let len = (next() % 32) as usize;
+            let bytes = (0..len).map(|_| next() as u8).collect::<Vec<_>>();
+            let text = String::from_utf8_lossy(&bytes);
+            if let Ok(roman) = Roman::checked_from_str(&text) {
+                assert!(u32::from(roman) > 0);
+            }
+
+            // bias towards roman digits to reach the regex more often
+            let digits = (0..len)
+                .map(|_| b"IVXLCDM "[(next() % 8) as usize] as char)
+                .collect::<String>();
+            if let Ok(roman) = Roman::checked_from_str(&digits) {
+                assert_eq!(roman, digits.trim());
+            }
+        }
+
+        assert!(Roman::checked_from_str(&"M".repeat(1_000_000)).is_err());
+        assert!(Roman::checked_from_str("XⅣ").is_err());
+        assert!(Roman::checked_from_str("\u{0}").is_err());
+    }
+
+    #[test]
+    fn subtractive_rules() {
+        let classic = SubtractiveRules::default();
+
+        // the rules agree with the classic syntax on every short numeral
+        let digits = ['I', 'V', 'X', 'L', 'C', 'D', 'M'];
+        let mut numerals = vec![String::new()];
+        for _ in 0..5 {
+            numerals = numerals
+                .iter()
+                .flat_map(|numeral| digits.iter().map(move |&digit| format!("{numeral}{digit}")))
+                .collect();
+            for numeral in &numerals {
+                let expected = Roman::try_from(numeral.as_str()).ok().map(u32::from);
+                assert_eq!(classic.value_of(numeral), expected, "{numeral}");
+            }
+        }
+
+        let mut permissive = SubtractiveRules::default();
+        assert!(permissive.allow('I', 'L'));
+        assert!(!permissive.allow('L', 'I'));
+        assert!(!permissive.allow('I', 'Q'));
+
+        // positive tests
+        assert_eq!(Roman::parse_with("IL", &permissive).unwrap(), "XLIX");
+        assert_eq!(Roman::parse_with("CIL", &permissive).unwrap(), "CXLIX");
+        assert_eq!(Roman::parse_with("XLIX", &permissive).unwrap(), "XLIX");
+        assert_eq!(Roman::parse_with(" XIV ", &classic).unwrap(), "XIV");
+
+        // negative tests
+        assert!(Roman::parse_with("IL", &classic).is_err());
+        assert!(Roman::parse_with("ILI", &permissive).is_err());
+        assert!(Roman::parse_with("LIL", &permissive).is_err());
+        assert!(Roman::parse_with("XIL", &permissive).is_err());
+        assert!(Roman::parse_with("IV", &SubtractiveRules::with_pairs([])).is_err());
+    }
+
+    #[test]
+    fn validate_detailed() {
+        // positive tests
+        assert_eq!(Roman::validate_detailed("I"), Ok(()));
+        assert_eq!(Roman::validate_detailed("MMMCMXCIX"), Ok(()));
+        // prefixes of valid numerals are valid, which the error position relies on
+        for roman in Roman::all() {
+            let text = roman.to_string();
+            assert_eq!(Roman::validate_detailed(&text), Ok(()), "{text}");
+            for end in 1..text.len() {
+                assert!(Roman::is_valid(&text[..end]), "{text}");
+            }
+        }
+
+        // bad characters
+        assert_eq!(Roman::validate_detailed("XLQII"), Err((2, 'Q')));
+        assert_eq!(Roman::validate_detailed("xlii"), Err((0, 'x')));
+        assert_eq!(Roman::validate_detailed("X IV"), Err((1, ' ')));
+        assert_eq!(Roman::validate_detailed("X€I"), Err((1, '€')));
+        assert_eq!(Roman::validate_detailed("IX€"), Err((2, '€')));
+
+        // rule breaks
+        assert_eq!(Roman::validate_detailed("IIII"), Err((3, 'I')));
+        assert_eq!(Roman::validate_detailed("XM"), Err((1, 'M')));
+        assert_eq!(Roman::validate_detailed("XCX"), Err((2, 'X')));
+        assert_eq!(Roman::validate_detailed("MMMM"), Err((3, 'M')));
+        assert_eq!(Roman::validate_detailed(""), Err((0, '\0')));
+    }
+
     #[test]
     fn conversion() {
         assert_eq!(u32::from(Roman::try_from("I").unwrap()), 1);
@@ -157,4 +720,211 @@ mod tests {
         assert_eq!(u32::from(Roman::try_from("CXXIV").unwrap()), 124);
         assert_eq!(u32::from(Roman::try_from("MMMCMIX").unwrap()), 3909);
     }
+
+    #[test]
+    fn digit_value() {
+        // positive tests
+        assert_eq!(Roman::digit_value('I'), Some(1));
+        assert_eq!(Roman::digit_value('V'), Some(5));
+        assert_eq!(Roman::digit_value('X'), Some(10));
+        assert_eq!(Roman::digit_value('L'), Some(50));
+        assert_eq!(Roman::digit_value('C'), Some(100));
+        assert_eq!(Roman::digit_value('D'), Some(500));
+        assert_eq!(Roman::digit_value('M'), Some(1000));
+
+        // negative tests
+        assert_eq!(Roman::digit_value('i'), None);
+        assert_eq!(Roman::digit_value('Q'), None);
+        assert_eq!(Roman::digit_value(' '), None);
+    }
+
+    #[test]
+    fn parse_all() {
+        let found = |text| {
+            Roman::parse_all(text)
+                .into_iter()
+                .map(|(roman, range)| (roman.to_string(), range))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            found("Chapter XIV, verse MMXXII."),
+            vec![
+                ("C".to_string(), 0..1),
+                ("XIV".to_string(), 8..11),
+                ("MMXXII".to_string(), 19..25),
+            ]
+        );
+
+        // adjacent numerals
+        assert_eq!(
+            found("IIII MMMM"),
+            vec![
+                ("III".to_string(), 0..3),
+                ("I".to_string(), 3..4),
+                ("MMM".to_string(), 5..8),
+                ("M".to_string(), 8..9),
+            ]
+        );
+        assert_eq!(
+            found("IXIX"),
+            vec![("IX".to_string(), 0..2), ("IX".to_string(), 2..4)]
+        );
+
+        // non-ASCII text around numerals
+        assert_eq!(found("€XII€"), vec![("XII".to_string(), 3..6)]);
+
+        // no numerals
+        assert!(found("").is_empty());
+        assert!(found("hello world").is_empty());
+    }
+
+    #[test]
+    fn digit_count() {
+        assert_eq!(Roman::try_from("I").unwrap().digit_count(), 1);
+        assert_eq!(Roman::try_from("XLII").unwrap().digit_count(), 4);
+        assert_eq!(Roman::try_from("MMMCMXCIX").unwrap().digit_count(), 9);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Roman::try_from("XLII").unwrap().to_string(), "XLII");
+        assert_eq!(Roman::try_from(3999u32).unwrap().to_string(), "MMMCMXCIX");
+    }
+
+    #[test]
+    fn lowercase() {
+        let roman = Roman::try_from("XLII").unwrap();
+        assert_eq!(roman.to_lowercase_string(), "xlii");
+        assert_eq!(format!("{roman:#}"), "xlii");
+        assert_eq!(format!("{roman}"), "XLII");
+
+        // parsing stays uppercase
+        assert!(Roman::try_from("xlii").is_err());
+    }
+
+    #[test]
+    fn str_equality() {
+        let roman = Roman::try_from("XLII").unwrap();
+
+        // positive tests
+        assert_eq!(roman, "XLII");
+        assert!(roman == *"XLII");
+
+        // negative tests
+        assert_ne!(roman, "XLI");
+        assert_ne!(roman, "xlii");
+        assert!(roman != *"");
+    }
+
+    #[test]
+    fn unknown_digit() {
+        // bypass validation to construct a broken numeral
+        let broken = Roman {
+            value: Cow::Borrowed("XQI"),
+        };
+        assert!(broken.try_value().is_err());
+        assert!(std::panic::catch_unwind(|| u32::from(&broken)).is_err());
+
+        let valid = Roman::try_from("XVI").unwrap();
+        assert_eq!(valid.try_value().unwrap(), 16);
+    }
+
+    #[test]
+    fn encoding() {
+        // positive tests
+        assert_eq!(
+            Roman::try_from(1u32).unwrap(),
+            Roman::try_from("I").unwrap()
+        );
+        assert_eq!(
+            Roman::try_from(42u32).unwrap(),
+            Roman::try_from("XLII").unwrap()
+        );
+        assert_eq!(
+            Roman::try_from(3999u32).unwrap(),
+            Roman::try_from("MMMCMXCIX").unwrap()
+        );
+        for value in 1..=3999 {
+            assert_eq!(u32::from(Roman::try_from(value).unwrap()), value);
+        }
+
+        // negative tests
+        assert!(Roman::try_from(0u32).is_err());
+        assert!(Roman::try_from(4000u32).is_err());
+    }
+
+    #[test]
+    fn wide_encoding() {
+        // positive tests
+        assert_eq!(Roman::try_from(42u64).unwrap(), "XLII");
+        assert_eq!(Roman::try_from(42i64).unwrap(), "XLII");
+        assert_eq!(Roman::try_from(3999u64).unwrap(), "MMMCMXCIX");
+        assert_eq!(Roman::try_from(3999i64).unwrap(), "MMMCMXCIX");
+
+        // negative tests
+        assert!(Roman::try_from(0u64).is_err());
+        assert!(Roman::try_from(0i64).is_err());
+        assert!(Roman::try_from(-42i64).is_err());
+        assert!(Roman::try_from(i64::MIN).is_err());
+        assert!(Roman::try_from(4000u64).is_err());
+        assert!(Roman::try_from(4000i64).is_err());
+        // values that would wrap around to a valid `u32` with `as`
+        assert!(Roman::try_from((1u64 << 32) + 42).is_err());
+        assert!(Roman::try_from(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn non_zero() {
+        for value in 1..=3999 {
+            let roman = Roman::try_from(value).unwrap();
+            assert_eq!(NonZeroU32::from(&roman).get(), u32::from(&roman));
+            assert_eq!(NonZeroU32::from(roman).get(), value);
+        }
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!(Roman::all().count(), 3999);
+        assert!(Roman::all()
+            .zip(1..)
+            .all(|(roman, value)| u32::from(roman) == value));
+        assert_eq!(Roman::all().last().unwrap(), "MMMCMXCIX");
+    }
+
+    #[test]
+    fn constants() {
+        let constants = [
+            (Roman::I, 1),
+            (Roman::V, 5),
+            (Roman::X, 10),
+            (Roman::L, 50),
+            (Roman::C, 100),
+            (Roman::D, 500),
+            (Roman::M, 1000),
+        ];
+
+        for (roman, value) in constants {
+            assert_eq!(Roman::try_from(value).unwrap(), roman);
+            assert_eq!(u32::from(roman), value);
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let two = Roman::try_from("II").unwrap();
+        let ten = Roman::try_from("X").unwrap();
+
+        // positive tests
+        assert_eq!(two.scale(3).unwrap(), Roman::try_from("VI").unwrap());
+        assert_eq!(ten.divide(4).unwrap(), Roman::try_from("II").unwrap());
+        assert_eq!(ten.divide(10).unwrap(), Roman::try_from("I").unwrap());
+
+        // negative tests
+        assert!(two.scale(0).is_none());
+        assert!(two.scale(2000).is_none());
+        assert!(two.scale(u32::MAX).is_none());
+        assert!(ten.divide(0).is_none());
+        assert!(ten.divide(11).is_none());
+    }
 }
@@ -0,0 +1,42 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use hashbrown::HashMap;
+
+use intra::language::Language;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn translate_ref_unknown_words_dont_allocate() {
+    let lang = Language::with(HashMap::from([
+        (Cow::from("glob"), 'I'),
+        (Cow::from("pish"), 'X'),
+    ]));
+    let long = vec!["glob"; 20].join(" ") + " foo";
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    assert!(lang.translate_ref("glob foo").is_err());
+    assert!(lang.translate_ref("pish pish bar").is_err());
+    assert!(lang.translate_ref(&long).is_err());
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert_eq!(after, before);
+}
@@ -0,0 +1,263 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+
+const BIN: &str = env!("CARGO_BIN_EXE_intra");
+
+#[test]
+fn strict() {
+    let output = Command::new(BIN)
+        .args(["--strict", "tests/test.txt"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected = std::fs::read_to_string("tests/test.out.txt").unwrap();
+    let expected = expected.lines().take(4).collect::<Vec<_>>();
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), expected);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("line 12: "));
+}
+
+#[test]
+fn lenient() {
+    let output = Command::new(BIN).arg("tests/test.txt").output().unwrap();
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let expected = std::fs::read_to_string("tests/test.out.txt").unwrap();
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        expected.lines().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn multiple_files() {
+    let output = Command::new(BIN)
+        .args(["tests/dialect.txt", "tests/questions.txt"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "glob prok is 4\nglob prok Silver is 68 Credits\n"
+    );
+
+    let output = Command::new(BIN)
+        .args(["tests/dialect.txt", "tests/missing.txt"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("tests/missing.txt"));
+}
+
+#[test]
+fn echo_acks() {
+    let output = Command::new(BIN).arg("tests/dialect.txt").output().unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let output = Command::new(BIN)
+        .args(["--echo-acks", "tests/dialect.txt"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], "OK: glob = I");
+    assert_eq!(lines[6], "OK: pish pish Iron = 3910 Credits");
+}
+
+#[test]
+fn translate() {
+    let output = Command::new(BIN)
+        .args([
+            "--load",
+            "tests/dialect.txt",
+            "translate",
+            "pish tegj glob glob",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "42\n");
+
+    let output = Command::new(BIN)
+        .args(["translate", "pish tegj glob glob"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn price() {
+    let output = Command::new(BIN)
+        .args(["price", "How many credits is glob prok Gold?"])
+        .args(["--load", "tests/dialect.txt"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "glob prok Gold is 57800 Credits\n"
+    );
+
+    let output = Command::new(BIN)
+        .args([
+            "--load",
+            "tests/dialect.txt",
+            "price",
+            "How many credits is glob Tin?",
+        ])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+#[test]
+fn repl() {
+    let mut child = Command::new(BIN)
+        .args(["--load", "tests/dialect.txt", "--no-history", "repl"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"how much is pish glob?\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("pish glob is 11"));
+}
+
+#[test]
+fn listen() {
+    let mut child = Command::new(BIN)
+        .args(["--listen", "127.0.0.1:0"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut banner = String::new();
+    stderr.read_line(&mut banner).unwrap();
+    let addr = banner
+        .trim()
+        .strip_prefix("Listening on ")
+        .unwrap()
+        .to_string();
+
+    let ask = |queries: &str, answers: usize| {
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream.write_all(queries.as_bytes()).unwrap();
+        let mut reader = BufReader::new(stream);
+        (0..answers)
+            .map(|_| {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let first = ask("glob is I\nHow much is glob glob?\nWho is glob?\n", 2);
+    // state is kept after the first client disconnects
+    let second = ask("How much is glob?\n", 1);
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert_eq!(
+        first,
+        [
+            "glob glob is 2",
+            "I have no idea what you are talking about"
+        ]
+    );
+    assert_eq!(second, ["glob is 1"]);
+}
+
+#[test]
+fn subcommand_conflicts() {
+    let conflicts: [&[&str]; 3] = [
+        &["--listen", "127.0.0.1:0", "repl"],
+        &["--strict", "translate", "glob"],
+        &["-o", "out.txt", "price", "How much is glob?"],
+    ];
+
+    for args in conflicts {
+        let output = Command::new(BIN).args(args).output().unwrap();
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("cannot be used with the subcommand"));
+    }
+}
+
+#[test]
+fn listen_without_paths() {
+    let output = Command::new(BIN)
+        .args(["--listen", "127.0.0.1:0", "tests/dialect.txt"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("cannot be used with"));
+}
+
+#[test]
+fn validate() {
+    let output = Command::new(BIN)
+        .args(["--validate", "tests/dialect.txt", "tests/questions.txt"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+
+    // unknown query form
+    let output = Command::new(BIN)
+        .args(["--validate", "tests/test.txt"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("line 12: "));
+    assert!(stderr.contains("1 problem(s) found"));
+
+    // conflicting definition
+    let output = Command::new(BIN)
+        .args(["--validate", "tests/conflict.txt"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("line 3: Word already exists: `glob`"));
+    assert!(stderr.contains("1 problem(s) found"));
+}